@@ -6,261 +6,2146 @@
 //! 简单的使用本双端队列，请使用slab_deque模块提供的双端队列
 //! 要查看本模块的用法，可以参照slab_deque模块，和https://github.com/GaiaWorld/pi_lib/tree/master/task_pool库
 
-use std::fmt::{Debug, Formatter, Result as FResult};
+use std::fmt::{Debug, Display, Formatter, Result as FResult};
+use std::error::Error;
 use std::marker::PhantomData;
 use std::mem::replace;
 use std::iter::Iterator;
+use std::cmp::Ordering as COrdering;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
 
 use pi_slab::IndexMap;
 
+/// 队列操作的错误
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DequeError {
+	/// 索引在索引工厂中不存在
+	NotFound(usize),
+	/// 索引对应的节点属于另一个队列
+	WrongOwner(usize),
+	/// 句柄已失效，其索引对应的节点已被删除（索引可能已被新的节点复用）
+	Stale(usize),
+	/// 队列为空
+	Empty,
+	/// 队列不存在，例如MultiDeque中的队列标识或PriorityDeque中的优先级超出范围
+	NoQueue(usize),
+}
+
+impl Display for DequeError {
+	fn fmt(&self, f: &mut Formatter) -> FResult {
+		match self {
+			DequeError::NotFound(index) => write!(f, "index {} not found", index),
+			DequeError::WrongOwner(index) => write!(f, "index {} belongs to another deque", index),
+			DequeError::Stale(index) => write!(f, "handle of index {} is stale", index),
+			DequeError::Empty => write!(f, "deque is empty"),
+			DequeError::NoQueue(queue) => write!(f, "queue {} does not exist", queue),
+		}
+	}
+}
+
+impl Error for DequeError {}
+
+/// 队列链表结构损坏的错误，由Deque::validate返回
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CorruptionError {
+	/// 发现损坏的节点索引
+	pub index: usize,
+	/// 损坏的类型
+	pub kind: CorruptionKind,
+}
+
+/// 链表结构损坏的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CorruptionKind {
+	/// 队列的first和last必须同时为0或同时不为0
+	UnpairedEnd,
+	/// 链表中的索引在索引工厂中不存在
+	NotFound,
+	/// 队列头部节点的pre不为0，值为实际的pre
+	FirstHasPre(usize),
+	/// 节点的pre与链表中的上一个节点不一致，值为实际的pre
+	BrokenPre(usize),
+	/// 队列尾部节点的next不为0，值为实际的next
+	LastHasNext(usize),
+	/// 链表在该节点结束，但该节点不是队列的尾部
+	EndsBeforeLast,
+	/// 节点标记的所属队列不是本队列，值为节点的owner
+	WrongOwner(u32),
+	/// 节点的顺序标签不大于上一个节点的标签，值为节点所在分组的全局标签和节点的局部标签
+	LabelOrder { group: u64, label: u32 },
+	/// 分组记录的首尾节点或节点个数与链表不一致，值为节点的分组
+	BadGroup(u32),
+	/// 链表中存在环
+	Cycle,
+	/// 链表的节点个数与队列长度不一致
+	LenMismatch { len: usize, count: usize },
+}
+
+impl Display for CorruptionError {
+	fn fmt(&self, f: &mut Formatter) -> FResult {
+		match self.kind {
+			CorruptionKind::UnpairedEnd => write!(f, "first and last of the deque are unpaired, index {}", self.index),
+			CorruptionKind::NotFound => write!(f, "linked index {} not found", self.index),
+			CorruptionKind::FirstHasPre(pre) => write!(f, "first index {} has pre {}", self.index, pre),
+			CorruptionKind::BrokenPre(pre) => write!(f, "index {} has broken pre {}", self.index, pre),
+			CorruptionKind::LastHasNext(next) => write!(f, "last index {} has next {}", self.index, next),
+			CorruptionKind::EndsBeforeLast => write!(f, "list ends at index {} before the last", self.index),
+			CorruptionKind::WrongOwner(owner) => write!(f, "index {} belongs to deque {}", self.index, owner),
+			CorruptionKind::LabelOrder { group, label } => write!(f, "index {} has out of order label ({}, {})", self.index, group, label),
+			CorruptionKind::BadGroup(group) => write!(f, "index {} does not match its label group {}", self.index, group),
+			CorruptionKind::Cycle => write!(f, "cycle detected at index {}", self.index),
+			CorruptionKind::LenMismatch { len, count } => write!(f, "deque len is {}, but {} nodes linked", len, count),
+		}
+	}
+}
+
+impl Error for CorruptionError {}
+
+/// 节点的代数分配器，节点第一次创建句柄时会分配一个新的代数（2^32次分配内不重复），没有创建过句柄的节点代数为0
+/// 由于索引工厂在删除节点时可能覆盖节点的内存，代数无法按槽位递增保存，因此使用全局计数；延迟到创建句柄时分配，不使用句柄的压入不需要原子操作
+static NEXT_GEN: AtomicU32 = AtomicU32::new(1);
+
+/// 队列标识的分配器，每个节点都标记了其所属队列的标识，用于识别共享索引工厂的其它队列的节点
+static NEXT_ID: AtomicU32 = AtomicU32::new(1);
+
+/// 从计数器分配一个不为0的值；代数和标识使用u32以减小节点的大小，计数分配2^32次后回绕，回绕后跳过0
+fn next_nonzero(counter: &AtomicU32) -> u32 {
+	loop {
+		let value = counter.fetch_add(1, Ordering::Relaxed);
+		if value != 0 {
+			return value;
+		}
+	}
+}
+
+/// 分组的最大节点数，不小于log2(n)（n < 2^64），见Deque::precedes
+const GROUP_SIZE: u32 = 64;
+
+/// 局部标签的范围为(0, LOCAL_END)
+const LOCAL_END: u64 = 1 << 32;
+
+/// 在分组两端追加节点时，新节点与相邻节点的局部标签间隔，顺序追加时每个分组可以容纳GROUP_SIZE - 1个节点
+const LOCAL_GAP: u64 = LOCAL_END / GROUP_SIZE as u64;
+
+/// 在队列两端添加分组时，新分组与相邻分组的全局标签间隔
+const LABEL_GAP: u64 = 1 << 32;
+
+/// 重新分配全局标签时的密度参数，取值范围为(1, 2)，越接近1，每次重新分配后的空隙越大；取1.25时，2^64的标签空间可容纳约2^43个分组
+const RELABEL_T: f64 = 1.25;
+
+/// 顺序标签的分组，一个分组的节点在链表中是连续的
+#[derive(Debug, Clone, Copy)]
+struct Group {
+	// 全局标签，沿链表严格递增
+	label: u64,
+	first: usize,
+	last: usize,
+	len: u32,
+}
+
+/// 带代数的元素句柄
+/// 节点被删除后，其索引会被索引工厂复用，而代数不会，因此可以用句柄识别出已失效的索引
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Handle {
+	index: usize,
+	gen: u32,
+}
+
+impl Handle {
+	/// 句柄对应的索引
+	pub fn index(&self) -> usize {
+		self.index
+	}
+
+	/// 句柄对应的代数
+	pub fn gen(&self) -> u32 {
+		self.gen
+	}
+}
+
+/// 有序插入时查找插入位置的起点
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchFrom {
+	/// 从队列头部向后查找，适合新元素通常较小的情况
+	Front,
+	/// 从队列尾部向前查找，适合新元素通常较大的情况（例如按截止时间排序的定时队列）
+	Back,
+}
+
 /// 双端队列
 pub struct Deque<T, C: IndexMap<Node<T>>>{
+	id: u32,
 	first : usize,
 	last :usize,
 	len: usize,
+	// 顺序标签的分组，下标即节点的group；free_groups为可复用的下标
+	groups: Vec<Group>,
+	free_groups: Vec<u32>,
+	// 是否在维护顺序标签；第一次调用precedes时才为整个队列分配标签，不使用precedes的队列不需要维护标签
+	labeled: bool,
 	mark: PhantomData<(T, C)>,
 }
 
-impl<T, C: IndexMap<Node<T>>> Default for Deque<T, C> {
-	fn default() -> Self {
-		Deque::new()
+impl<T, C: IndexMap<Node<T>>> Default for Deque<T, C> {
+	fn default() -> Self {
+		Deque::new()
+	}
+}
+
+impl<T, C: IndexMap<Node<T>>> Deque<T, C> {
+	pub fn new() -> Self {
+		Self {
+			id: next_nonzero(&NEXT_ID),
+			first: 0,
+			last: 0,
+			len: 0,
+			groups: Vec::new(),
+			free_groups: Vec::new(),
+			labeled: false,
+			mark: PhantomData,
+		}
+	}
+
+	/// 队列的标识，本队列的节点的owner都等于该标识；append可能交换两个队列的标识
+	pub fn id(&self) -> u32 {
+		self.id
+	}
+
+	pub fn get_first(&self) -> usize {
+		self.first
+	}
+
+	pub fn get_last(&self) -> usize {
+		self.last
+	}
+
+	/// Returns a reference to the first element, or None if the Deque is empty.
+	pub fn front<'a>(&self, index_map: &'a C) -> Option<&'a T> {
+		if self.first == 0 {
+			None
+		} else {
+			Some(unsafe { &index_map.get_unchecked(self.first).elem })
+		}
+	}
+
+	/// Returns a mutable reference to the first element, or None if the Deque is empty.
+	pub fn front_mut<'a>(&self, index_map: &'a mut C) -> Option<&'a mut T> {
+		if self.first == 0 {
+			None
+		} else {
+			Some(unsafe { &mut index_map.get_unchecked_mut(self.first).elem })
+		}
+	}
+
+	/// Returns a reference to the last element, or None if the Deque is empty.
+	pub fn back<'a>(&self, index_map: &'a C) -> Option<&'a T> {
+		if self.last == 0 {
+			None
+		} else {
+			Some(unsafe { &index_map.get_unchecked(self.last).elem })
+		}
+	}
+
+	/// Returns a mutable reference to the last element, or None if the Deque is empty.
+	pub fn back_mut<'a>(&self, index_map: &'a mut C) -> Option<&'a mut T> {
+		if self.last == 0 {
+			None
+		} else {
+			Some(unsafe { &mut index_map.get_unchecked_mut(self.last).elem })
+		}
+	}
+
+	/// Returns true if the element at index is in this Deque.
+	pub fn contains(&self, index: usize, index_map: &C) -> bool {
+		match index_map.get(index) {
+			Some(node) => node.owner == self.id,
+			None => false,
+		}
+	}
+
+	/// Returns a reference to the element at index, or None if index is not an element of this Deque.
+	pub fn get<'a>(&self, index: usize, index_map: &'a C) -> Option<&'a T> {
+		match index_map.get(index) {
+			Some(node) if node.owner == self.id => Some(&node.elem),
+			_ => None,
+		}
+	}
+
+	/// Returns a mutable reference to the element at index, or None if index is not an element of this Deque.
+	pub fn get_mut<'a>(&self, index: usize, index_map: &'a mut C) -> Option<&'a mut T> {
+		match index_map.get_mut(index) {
+			Some(node) if node.owner == self.id => Some(&mut node.elem),
+			_ => None,
+		}
+	}
+
+	/// Returns the index of the element after the element at index, or None if it is the last or index is not an element of this Deque.
+	pub fn next_of(&self, index: usize, index_map: &C) -> Option<usize> {
+		match index_map.get(index) {
+			Some(node) if node.owner == self.id && node.next != 0 => Some(node.next),
+			_ => None,
+		}
+	}
+
+	/// Returns the index of the element before the element at index, or None if it is the first or index is not an element of this Deque.
+	pub fn pre_of(&self, index: usize, index_map: &C) -> Option<usize> {
+		match index_map.get(index) {
+			Some(node) if node.owner == self.id && node.pre != 0 => Some(node.pre),
+			_ => None,
+		}
+	}
+
+	/// Returns the index of the n-th element from the front (starting from 0), or None if n is out of bounds.
+	/// 从距离较近的一端开始遍历，时间复杂度O(min(n, len - n))
+	pub fn nth(&self, n: usize, index_map: &C) -> Option<usize> {
+		if n >= self.len {
+			return None;
+		}
+		if n < self.len / 2 {
+			let mut index = self.first;
+			for _ in 0..n {
+				index = unsafe { index_map.get_unchecked(index).next };
+			}
+			Some(index)
+		} else {
+			let mut index = self.last;
+			for _ in 0..(self.len - 1 - n) {
+				index = unsafe { index_map.get_unchecked(index).pre };
+			}
+			Some(index)
+		}
+	}
+
+	/// Returns the distance from the front of the Deque to the element at index, or None if index is not an element of this Deque.
+	/// 同时向两端遍历，时间复杂度O(min(position, len - position))
+	pub fn position_of(&self, index: usize, index_map: &C) -> Option<usize> {
+		if !self.contains(index, index_map) {
+			return None;
+		}
+		let (mut pre, mut next) = (index, index);
+		let mut steps = 0;
+		loop {
+			pre = unsafe { index_map.get_unchecked(pre).pre };
+			if pre == 0 {
+				return Some(steps);
+			}
+			next = unsafe { index_map.get_unchecked(next).next };
+			if next == 0 {
+				return Some(self.len - 1 - steps);
+			}
+			steps += 1;
+		}
+	}
+
+	/// Returns true if the element at a comes before the element at b in O(1), both must be elements of this Deque.
+	/// 顺序标签分为两层（Dietz和Sleator的两层顺序维护结构）：链表被划分为不超过GROUP_SIZE个连续节点的分组，
+	/// 节点在分组内有局部标签，分组有全局标签，（全局标签，局部标签）沿链表严格递增，比较即可得到先后关系
+	/// 插入节点只修改所在分组的局部标签（O(GROUP_SIZE)）；分组满或局部标签用尽时才拆分分组，新分组的全局标签按Bender等人的算法分配，
+	/// 均摊重新分配O(log n)个分组的全局标签，每个分组只需修改一个标签；由于两次拆分之间至少有GROUP_SIZE / 2次插入且GROUP_SIZE不小于log2(n)，每次插入的均摊开销为O(1)
+	/// 标签在第一次调用时才为整个队列分配（O(n)，均摊到此前的插入上），此后随插入和删除维护；不调用本方法的队列不需要维护标签
+	pub fn precedes(&mut self, a: usize, b: usize, index_map: &mut C) -> Result<bool, DequeError> {
+		self.check_node(a, index_map)?;
+		self.check_node(b, index_map)?;
+		if !self.labeled {
+			self.regroup_all(index_map);
+		}
+		Ok(unsafe { self.order_key(a, index_map) < self.order_key(b, index_map) })
+	}
+
+	// 节点的顺序键，沿链表严格递增，index必须是本队列的节点
+	unsafe fn order_key(&self, index: usize, index_map: &C) -> (u64, u32) {
+		let node = index_map.get_unchecked(index);
+		(self.groups[node.group as usize].label, node.label)
+	}
+
+	/// Returns the handle of the element at index, or None if index is not an element of this Deque.
+	/// 节点第一次创建句柄时才分配代数，因此需要可变的索引工厂
+	pub fn handle(&self, index: usize, index_map: &mut C) -> Option<Handle> {
+		match index_map.get(index) {
+			Some(node) if node.owner == self.id => Some(unsafe { Self::make_handle(index, index_map) }),
+			_ => None,
+		}
+	}
+
+	// 为index对应的节点创建句柄，节点还没有代数时为其分配代数，index必须在索引工厂中
+	unsafe fn make_handle(index: usize, index_map: &mut C) -> Handle {
+		let node = index_map.get_unchecked_mut(index);
+		if node.gen == 0 {
+			node.gen = next_nonzero(&NEXT_GEN);
+		}
+		Handle { index, gen: node.gen }
+	}
+
+	/// Returns true if the element of the handle is still in this Deque.
+	pub fn contains_handle(&self, handle: Handle, index_map: &C) -> bool {
+		match index_map.get(handle.index) {
+			Some(node) => node.gen == handle.gen && node.owner == self.id,
+			None => false,
+		}
+	}
+
+	/// Returns a reference to the element of the handle, or None if the handle is stale.
+	pub fn get_by_handle<'a>(&self, handle: Handle, index_map: &'a C) -> Option<&'a T> {
+		match index_map.get(handle.index) {
+			Some(node) if node.gen == handle.gen && node.owner == self.id => Some(&node.elem),
+			_ => None,
+		}
+	}
+
+	/// Returns a mutable reference to the element of the handle, or None if the handle is stale.
+	pub fn get_mut_by_handle<'a>(&self, handle: Handle, index_map: &'a mut C) -> Option<&'a mut T> {
+		match index_map.get_mut(handle.index) {
+			Some(node) if node.gen == handle.gen && node.owner == self.id => Some(&mut node.elem),
+			_ => None,
+		}
+	}
+
+	/// Append an element to the Deque. return the handle of the element
+	pub fn push_back_handle(&mut self, elem: T, index_map: &mut C) -> Handle {
+		let index = self.push_back(elem, index_map);
+		unsafe { Self::make_handle(index, index_map) }
+	}
+
+	/// Prepend an element to the Deque. return the handle of the element
+	pub fn push_front_handle(&mut self, elem: T, index_map: &mut C) -> Handle {
+		let index = self.push_front(elem, index_map);
+		unsafe { Self::make_handle(index, index_map) }
+	}
+
+	/// Inserts an element after the anchor element. return the handle of the element
+	///
+	/// Returns an error if the anchor handle is stale, or belongs to another Deque.
+	pub fn insert_after_handle(&mut self, anchor: Handle, elem: T, index_map: &mut C) -> Result<Handle, DequeError> {
+		self.check_handle(anchor, index_map)?;
+		let index = unsafe { self.insert_after_unchecked(elem, anchor.index, index_map) };
+		Ok(unsafe { Self::make_handle(index, index_map) })
+	}
+
+	/// Inserts an element before the anchor element. return the handle of the element
+	///
+	/// Returns an error if the anchor handle is stale, or belongs to another Deque.
+	pub fn insert_before_handle(&mut self, anchor: Handle, elem: T, index_map: &mut C) -> Result<Handle, DequeError> {
+		self.check_handle(anchor, index_map)?;
+		let index = unsafe { self.insert_before_unchecked(elem, anchor.index, index_map) };
+		Ok(unsafe { Self::make_handle(index, index_map) })
+	}
+
+	// 检查句柄是否有效，且其元素为本队列的节点
+	fn check_handle(&self, handle: Handle, index_map: &C) -> Result<(), DequeError> {
+		match index_map.get(handle.index) {
+			Some(node) if node.gen == handle.gen => (),
+			_ => return Err(DequeError::Stale(handle.index)),
+		}
+		self.check_node(handle.index, index_map)
+	}
+
+	/// Append an element to the Deque. return a index
+	pub fn push_back(&mut self, elem: T, index_map: &mut C) -> usize {
+		let index = index_map.insert(Node::new(elem, self.last, 0, self.id));
+		unsafe { self.attach_back(index, index_map) };
+		index
+	}
+
+	/// Prepend an element to the Deque. return a index
+	pub fn push_front(&mut self, elem: T, index_map: &mut C) -> usize{
+		let index = index_map.insert(Node::new(elem, 0, self.first, self.id));
+		unsafe { self.attach_front(index, index_map) };
+		index
+	}
+
+	/// Inserts an element after the anchor element. return a index
+	///
+	/// Returns an error if anchor is not in the index map, or belongs to another Deque.
+	pub fn insert_after(&mut self, anchor: usize, elem: T, index_map: &mut C) -> Result<usize, DequeError> {
+		self.check_node(anchor, index_map)?;
+		Ok(unsafe { self.insert_after_unchecked(elem, anchor, index_map) })
+	}
+
+	/// Inserts an element before the anchor element. return a index
+	///
+	/// Returns an error if anchor is not in the index map, or belongs to another Deque.
+	pub fn insert_before(&mut self, anchor: usize, elem: T, index_map: &mut C) -> Result<usize, DequeError> {
+		self.check_node(anchor, index_map)?;
+		Ok(unsafe { self.insert_before_unchecked(elem, anchor, index_map) })
+	}
+
+	/// Append an element to the Deque. return a index
+	///
+	/// # Safety
+	///
+	/// `index` must be the index of a node of this Deque, or it panics.
+	#[deprecated(note = "use the safe `insert_after` instead")]
+	pub unsafe fn push_to_back(&mut self, elem: T, index: usize, index_map: &mut C) -> usize{
+		if let Err(e) = self.check_node(index, index_map) {
+			panic!("{}", e);
+		}
+		self.insert_after_unchecked(elem, index, index_map)
+	}
+
+	/// Prepend an element to the Deque. return a index
+	///
+	/// # Safety
+	///
+	/// `index` must be the index of a node of this Deque, or it panics.
+	#[deprecated(note = "use the safe `insert_before` instead")]
+	pub unsafe fn push_to_front(&mut self, elem: T, index: usize, index_map: &mut C) -> usize{
+		if let Err(e) = self.check_node(index, index_map) {
+			panic!("{}", e);
+		}
+		self.insert_before_unchecked(elem, index, index_map)
+	}
+
+	// 检查index是否为本队列的节点
+	fn check_node(&self, index: usize, index_map: &C) -> Result<(), DequeError> {
+		let node = match index_map.get(index) {
+			Some(node) => node,
+			None => return Err(DequeError::NotFound(index)),
+		};
+		if node.owner != self.id {
+			return Err(DequeError::WrongOwner(index));
+		}
+		Ok(())
+	}
+
+	/// Moves the element at index to the front of the Deque, the index of the element is unchanged.
+	pub fn move_to_front(&mut self, index: usize, index_map: &mut C) -> Result<(), DequeError> {
+		self.check_node(index, index_map)?;
+		if self.first != index {
+			unsafe {
+				self.unlink(index, index_map);
+				self.link_front(index, index_map);
+			}
+		}
+		Ok(())
+	}
+
+	/// Moves the element at index to the back of the Deque, the index of the element is unchanged.
+	pub fn move_to_back(&mut self, index: usize, index_map: &mut C) -> Result<(), DequeError> {
+		self.check_node(index, index_map)?;
+		if self.last != index {
+			unsafe {
+				self.unlink(index, index_map);
+				self.link_back(index, index_map);
+			}
+		}
+		Ok(())
+	}
+
+	/// Moves the element at index to the position after the anchor element, the index of the element is unchanged.
+	pub fn move_after(&mut self, index: usize, anchor: usize, index_map: &mut C) -> Result<(), DequeError> {
+		self.check_node(index, index_map)?;
+		self.check_node(anchor, index_map)?;
+		if index != anchor {
+			unsafe {
+				self.unlink(index, index_map);
+				self.link_after(index, anchor, index_map);
+			}
+		}
+		Ok(())
+	}
+
+	/// Moves the element at index to the position before the anchor element, the index of the element is unchanged.
+	pub fn move_before(&mut self, index: usize, anchor: usize, index_map: &mut C) -> Result<(), DequeError> {
+		self.check_node(index, index_map)?;
+		self.check_node(anchor, index_map)?;
+		if index != anchor {
+			unsafe {
+				self.unlink(index, index_map);
+				self.link_before(index, anchor, index_map);
+			}
+		}
+		Ok(())
+	}
+
+	/// Moves the element at index to the back of another Deque sharing the same index_map, the index and handle of the element are unchanged.
+	pub fn transfer_to_back(&mut self, index: usize, other: &mut Self, index_map: &mut C) -> Result<(), DequeError> {
+		self.check_node(index, index_map)?;
+		unsafe {
+			self.unlink(index, index_map);
+			other.link_back(index, index_map);
+		}
+		Ok(())
+	}
+
+	/// Moves the element at index to the front of another Deque sharing the same index_map, the index and handle of the element are unchanged.
+	pub fn transfer_to_front(&mut self, index: usize, other: &mut Self, index_map: &mut C) -> Result<(), DequeError> {
+		self.check_node(index, index_map)?;
+		unsafe {
+			self.unlink(index, index_map);
+			other.link_front(index, index_map);
+		}
+		Ok(())
+	}
+
+	// index必须是本队列的节点
+	unsafe fn insert_after_unchecked(&mut self, elem: T, index: usize, index_map: &mut C) -> usize{
+		let i = index_map.insert(Node::new(elem, 0, 0, 0));
+		self.link_after(i, index, index_map);
+		i
+	}
+
+	// index必须是本队列的节点
+	unsafe fn insert_before_unchecked(&mut self, elem: T, index: usize, index_map: &mut C) -> usize{
+		let i = index_map.insert(Node::new(elem, 0, 0, 0));
+		self.link_before(i, index, index_map);
+		i
+	}
+
+	// 将一个在索引工厂中、但不在任何链表中的节点链接到队列尾部
+	unsafe fn link_back(&mut self, index: usize, index_map: &mut C) {
+		{
+			let node = index_map.get_unchecked_mut(index);
+			node.pre = self.last;
+			node.next = 0;
+			node.owner = self.id;
+		}
+		self.attach_back(index, index_map);
+	}
+
+	// 将pre已指向队列尾部、next为0的节点接到队列尾部
+	unsafe fn attach_back(&mut self, index: usize, index_map: &mut C) {
+		if self.last == 0 {
+			self.first = index;
+		} else {
+			index_map.get_unchecked_mut(self.last).next = index;
+		}
+		self.last = index;
+		self.len += 1;
+		if self.labeled {
+			self.place_label(index, index_map);
+		}
+	}
+
+	// 将一个在索引工厂中、但不在任何链表中的节点链接到队列头部
+	unsafe fn link_front(&mut self, index: usize, index_map: &mut C) {
+		{
+			let node = index_map.get_unchecked_mut(index);
+			node.pre = 0;
+			node.next = self.first;
+			node.owner = self.id;
+		}
+		self.attach_front(index, index_map);
+	}
+
+	// 将next已指向队列头部、pre为0的节点接到队列头部
+	unsafe fn attach_front(&mut self, index: usize, index_map: &mut C) {
+		if self.first == 0 {
+			self.last = index;
+		} else {
+			index_map.get_unchecked_mut(self.first).pre = index;
+		}
+		self.first = index;
+		self.len += 1;
+		if self.labeled {
+			self.place_label(index, index_map);
+		}
+	}
+
+	// 将一个在索引工厂中、但不在任何链表中的节点链接到anchor之后，anchor必须是本队列的节点
+	unsafe fn link_after(&mut self, index: usize, anchor: usize, index_map: &mut C) {
+		let next = replace(&mut index_map.get_unchecked_mut(anchor).next, index);
+		{
+			let node = index_map.get_unchecked_mut(index);
+			node.pre = anchor;
+			node.next = next;
+			node.owner = self.id;
+		}
+		if next == 0 {
+			self.last = index;
+		} else {
+			index_map.get_unchecked_mut(next).pre = index;
+		}
+		self.len += 1;
+		if self.labeled {
+			self.place_label(index, index_map);
+		}
+	}
+
+	// 将一个在索引工厂中、但不在任何链表中的节点链接到anchor之前，anchor必须是本队列的节点
+	unsafe fn link_before(&mut self, index: usize, anchor: usize, index_map: &mut C) {
+		let pre = replace(&mut index_map.get_unchecked_mut(anchor).pre, index);
+		{
+			let node = index_map.get_unchecked_mut(index);
+			node.pre = pre;
+			node.next = anchor;
+			node.owner = self.id;
+		}
+		if pre == 0 {
+			self.first = index;
+		} else {
+			index_map.get_unchecked_mut(pre).next = index;
+		}
+		self.len += 1;
+		if self.labeled {
+			self.place_label(index, index_map);
+		}
+	}
+
+	// 为刚链接到链表中的节点分配分组和局部标签，使顺序键沿链表严格递增
+	// 节点优先加入前一个节点的分组，没有前一个节点时加入后一个节点的分组
+	unsafe fn place_label(&mut self, index: usize, index_map: &mut C) {
+		let (pre, next) = {
+			let node = index_map.get_unchecked(index);
+			(node.pre, node.next)
+		};
+		let (g, lo, hi) = match (pre, next) {
+			(0, 0) => {
+				let g = self.alloc_group(Group { label: u64::MAX / 2, first: index, last: index, len: 1 });
+				let node = index_map.get_unchecked_mut(index);
+				node.group = g;
+				node.label = (LOCAL_END / 2) as u32;
+				return;
+			},
+			(0, _) => {
+				let node = index_map.get_unchecked(next);
+				(node.group, 0, node.label as u64)
+			},
+			_ => {
+				let g = index_map.get_unchecked(pre).group;
+				let hi = match next {
+					0 => LOCAL_END,
+					_ => match index_map.get_unchecked(next) {
+						node if node.group == g => node.label as u64,
+						_ => LOCAL_END,
+					},
+				};
+				(g, index_map.get_unchecked(pre).label as u64, hi)
+			},
+		};
+		let full = self.groups[g as usize].len >= GROUP_SIZE;
+		// 在队列两端压入时，分组满了就在外侧添加新的分组，不需要拆分
+		let label = match (pre, next) {
+			(_, 0) if full || lo + LOCAL_GAP >= hi => return self.push_group(index, g, true, index_map),
+			(0, _) if full || hi <= LOCAL_GAP => return self.push_group(index, g, false, index_map),
+			(_, 0) => lo + LOCAL_GAP,
+			(0, _) => hi - LOCAL_GAP,
+			_ => lo + (hi - lo) / 2,
+		};
+		let group = &mut self.groups[g as usize];
+		group.len += 1;
+		if pre == 0 {
+			group.first = index;
+		} else if group.last == pre {
+			group.last = index;
+		}
+		let node = index_map.get_unchecked_mut(index);
+		node.group = g;
+		node.label = label as u32;
+		if full || hi - lo < 2 {
+			self.rebalance_group(g, index_map);
+		}
+	}
+
+	// 在分组g之后（after为true）或之前添加只有index一个节点的新分组，index必须在链表中与分组g相邻
+	unsafe fn push_group(&mut self, index: usize, g: u32, after: bool, index_map: &mut C) {
+		let h = self.alloc_group(Group { label: 0, first: index, last: index, len: 1 });
+		let node = index_map.get_unchecked_mut(index);
+		node.group = h;
+		node.label = match after {
+			true => LOCAL_GAP,
+			false => LOCAL_END - LOCAL_GAP,
+		} as u32;
+		let label = self.groups[g as usize].label;
+		let label = match after {
+			true => label.checked_add(LABEL_GAP),
+			false => label.checked_sub(LABEL_GAP).filter(|label| *label > 0),
+		};
+		match label {
+			Some(label) => self.groups[h as usize].label = label,
+			None => self.place_group(h, index_map),
+		}
+	}
+
+	// 分组超出GROUP_SIZE或局部标签用尽时调用：节点不超过GROUP_SIZE / 2时在分组内重新均匀分配局部标签，否则将分组对半拆分
+	unsafe fn rebalance_group(&mut self, g: u32, index_map: &mut C) {
+		let Group { first, last, len, .. } = self.groups[g as usize];
+		if len <= GROUP_SIZE / 2 {
+			Self::spread_labels(first, len, index_map);
+			return;
+		}
+		let keep = len / 2;
+		let mut mid = first;
+		for _ in 0..keep {
+			mid = index_map.get_unchecked(mid).next;
+		}
+		let h = self.alloc_group(Group { label: 0, first: mid, last, len: len - keep });
+		let group = &mut self.groups[g as usize];
+		group.last = index_map.get_unchecked(mid).pre;
+		group.len = keep;
+		let mut next = mid;
+		for _ in keep..len {
+			let node = index_map.get_unchecked_mut(next);
+			node.group = h;
+			next = node.next;
+		}
+		Self::spread_labels(first, keep, index_map);
+		Self::spread_labels(mid, len - keep, index_map);
+		self.place_group(h, index_map);
+	}
+
+	// 在整个局部标签范围内为从first开始的len个节点均匀分配局部标签
+	unsafe fn spread_labels(first: usize, len: u32, index_map: &mut C) {
+		let step = LOCAL_END / (len as u64 + 1);
+		let mut label = 0;
+		let mut next = first;
+		for _ in 0..len {
+			label += step;
+			let node = index_map.get_unchecked_mut(next);
+			node.label = label as u32;
+			next = node.next;
+		}
+	}
+
+	// 为刚链接到链表中的分组h分配全局标签
+	unsafe fn place_group(&mut self, h: u32, index_map: &mut C) {
+		let pre = self.pre_group(h, index_map);
+		let next = self.next_group(h, index_map);
+		let lo = pre.map_or(0, |g| self.groups[g as usize].label);
+		let hi = next.map_or(u64::MAX, |g| self.groups[g as usize].label);
+		if hi - lo < 2 {
+			self.relabel_around(h, index_map);
+			return;
+		}
+		self.groups[h as usize].label = match (pre, next) {
+			(None, None) => u64::MAX / 2,
+			(_, None) if hi - lo > LABEL_GAP => lo + LABEL_GAP,
+			(None, _) if hi - lo > LABEL_GAP => hi - LABEL_GAP,
+			_ => lo + (hi - lo) / 2,
+		};
+	}
+
+	// 全局标签没有空隙时，按Bender等人的顺序维护算法（Two Simplified Algorithms for Maintaining Order in a List, 2002）重新分配分组的全局标签：
+	// 从包含相邻分组标签、大小为2^i的对齐标签区间开始，逐步增大i，直到区间内的分组个数不超过(2/RELABEL_T)^i，再在该区间内均匀分配标签
+	// 每添加一个分组，均摊需要重新分配O(log n)个分组的全局标签
+	unsafe fn relabel_around(&mut self, h: u32, index_map: &mut C) {
+		// 新分组还没有标签，用相邻分组的标签确定区间
+		let anchor = match self.pre_group(h, index_map) {
+			Some(g) => self.groups[g as usize].label,
+			None => self.groups[self.next_group(h, index_map).unwrap() as usize].label,
+		};
+		let (mut l, mut r) = (h, h);
+		let mut count: u64 = 1;
+		let mut capacity: f64 = 1.0;
+		for bits in 1..=64 {
+			capacity *= 2.0 / RELABEL_T;
+			let mask = match bits {
+				64 => u64::MAX,
+				_ => (1u64 << bits) - 1,
+			};
+			let (lo, hi) = (anchor & !mask, anchor | mask);
+			// 区间内的分组在链表中是连续的，从上一轮的边界继续向两侧扩展
+			while let Some(g) = self.pre_group(l, index_map) {
+				if self.groups[g as usize].label < lo {
+					break;
+				}
+				l = g;
+				count += 1;
+			}
+			while let Some(g) = self.next_group(r, index_map) {
+				if self.groups[g as usize].label > hi {
+					break;
+				}
+				r = g;
+				count += 1;
+			}
+			if count as f64 <= capacity || bits == 64 {
+				let step = ((hi - lo) as u128 + 1) / (count as u128 + 1);
+				let mut label = lo as u128;
+				let mut g = l;
+				loop {
+					label += step;
+					self.groups[g as usize].label = label as u64;
+					if g == r {
+						return;
+					}
+					g = self.next_group(g, index_map).unwrap();
+				}
+			}
+		}
+	}
+
+	// 链表中分组g之前的分组
+	unsafe fn pre_group(&self, g: u32, index_map: &C) -> Option<u32> {
+		match index_map.get_unchecked(self.groups[g as usize].first).pre {
+			0 => None,
+			pre => Some(index_map.get_unchecked(pre).group),
+		}
+	}
+
+	// 链表中分组g之后的分组
+	unsafe fn next_group(&self, g: u32, index_map: &C) -> Option<u32> {
+		match index_map.get_unchecked(self.groups[g as usize].last).next {
+			0 => None,
+			next => Some(index_map.get_unchecked(next).group),
+		}
+	}
+
+	fn alloc_group(&mut self, group: Group) -> u32 {
+		match self.free_groups.pop() {
+			Some(g) => {
+				self.groups[g as usize] = group;
+				g
+			},
+			None => {
+				let g = u32::try_from(self.groups.len()).expect("too many label groups");
+				self.groups.push(group);
+				g
+			},
+		}
+	}
+
+	// 节点从链表中摘除或删除时，更新其所在的分组，pre和next为节点原来的相邻节点
+	fn leave_group(&mut self, index: usize, g: u32, pre: usize, next: usize) {
+		let group = &mut self.groups[g as usize];
+		group.len -= 1;
+		if group.len == 0 {
+			self.free_groups.push(g);
+		} else if group.first == index {
+			group.first = next;
+		} else if group.last == index {
+			group.last = pre;
+		}
+	}
+
+	// 将x所在的分组从x处拆分，x及其之后的节点移入一个新分组，新分组暂时使用原分组的全局标签，需要由调用者修正
+	unsafe fn cut_group(&mut self, x: usize, index_map: &mut C) {
+		let g = index_map.get_unchecked(x).group;
+		let Group { label, first, last, .. } = self.groups[g as usize];
+		if first == x {
+			return;
+		}
+		let h = self.alloc_group(Group { label, first: x, last, len: 0 });
+		let mut len = 0;
+		let mut next = x;
+		loop {
+			let node = index_map.get_unchecked_mut(next);
+			node.group = h;
+			len += 1;
+			if next == last {
+				break;
+			}
+			next = node.next;
+		}
+		self.groups[h as usize].len = len;
+		let group = &mut self.groups[g as usize];
+		group.len -= len;
+		group.last = index_map.get_unchecked(x).pre;
+	}
+
+	// 将链表中从first到last的连续节点的分组转移到to中，节点保留原来的全局和局部标签
+	// 这段节点必须包含队列的头部或尾部，调用时链表尚未断开
+	unsafe fn move_groups(&mut self, first: usize, last: usize, to: &mut Self, index_map: &mut C) {
+		let before = index_map.get_unchecked(first).pre;
+		let after = index_map.get_unchecked(last).next;
+		let mut start = first;
+		loop {
+			// 一段属于同一个分组的节点
+			let g = index_map.get_unchecked(start).group;
+			let h = to.alloc_group(Group { label: self.groups[g as usize].label, first: start, last: start, len: 0 });
+			let mut end = start;
+			let mut len = 1;
+			loop {
+				index_map.get_unchecked_mut(end).group = h;
+				if end == last {
+					break;
+				}
+				let next = index_map.get_unchecked(end).next;
+				if index_map.get_unchecked(next).group != g {
+					break;
+				}
+				end = next;
+				len += 1;
+			}
+			let moved = &mut to.groups[h as usize];
+			moved.last = end;
+			moved.len = len;
+			let group = &mut self.groups[g as usize];
+			group.len -= len;
+			if group.len == 0 {
+				self.free_groups.push(g);
+			} else if group.first == start {
+				group.first = after;
+			} else {
+				group.last = before;
+			}
+			if end == last {
+				return;
+			}
+			start = index_map.get_unchecked(end).next;
+		}
+	}
+
+	// 拼接或旋转后，使连接处两侧分组的全局标签保持递增；只调整forward指定的一侧的分组，直到标签重新有序
+	unsafe fn fix_group_labels(&mut self, pre: u32, next: u32, forward: bool, index_map: &mut C) {
+		if self.groups[pre as usize].label < self.groups[next as usize].label {
+			return;
+		}
+		if forward {
+			let (mut pre, mut next) = (pre, Some(next));
+			while let Some(g) = next {
+				let label = match self.groups[pre as usize].label.checked_add(LABEL_GAP) {
+					Some(label) => label,
+					None => return self.regroup_all(index_map),
+				};
+				if self.groups[g as usize].label > label {
+					return;
+				}
+				self.groups[g as usize].label = label;
+				pre = g;
+				next = self.next_group(g, index_map);
+			}
+		} else {
+			let (mut pre, mut next) = (Some(pre), next);
+			while let Some(g) = pre {
+				let label = match self.groups[next as usize].label.checked_sub(LABEL_GAP) {
+					Some(label) if label > 0 => label,
+					_ => return self.regroup_all(index_map),
+				};
+				if self.groups[g as usize].label < label {
+					return;
+				}
+				self.groups[g as usize].label = label;
+				next = g;
+				pre = self.pre_group(g, index_map);
+			}
+		}
+	}
+
+	// 停止维护顺序标签，下次调用precedes时重新分配
+	fn drop_labels(&mut self) {
+		self.groups.clear();
+		self.free_groups.clear();
+		self.labeled = false;
+	}
+
+	// 为整个队列重新划分分组，每个分组GROUP_SIZE / 2个节点，在整个标签范围内均匀分配全局标签和局部标签
+	fn regroup_all(&mut self, index_map: &mut C) {
+		self.groups.clear();
+		self.free_groups.clear();
+		self.labeled = true;
+		let size = GROUP_SIZE / 2;
+		let step = u64::MAX / (self.len as u64 / size as u64 + 2);
+		let mut label = 0;
+		let mut next = self.first;
+		while next != 0 {
+			label += step;
+			let first = next;
+			let mut last = next;
+			let mut len = 0;
+			let g = self.alloc_group(Group { label, first, last, len });
+			while next != 0 && len < size {
+				let node = unsafe { index_map.get_unchecked_mut(next) };
+				node.group = g;
+				last = next;
+				next = node.next;
+				len += 1;
+			}
+			let group = &mut self.groups[g as usize];
+			group.last = last;
+			group.len = len;
+			unsafe { Self::spread_labels(first, len, index_map) };
+		}
+	}
+
+	// 将index对应的节点从链表中摘除，节点仍然保留在索引工厂中，index必须是本队列的节点
+	unsafe fn unlink(&mut self, index: usize, index_map: &mut C) {
+		let (pre, next, group) = {
+			let node = index_map.get_unchecked_mut(index);
+			(replace(&mut node.pre, 0), replace(&mut node.next, 0), node.group)
+		};
+		if self.labeled {
+			self.leave_group(index, group, pre, next);
+		}
+		match (pre, next) {
+			(0, 0) => {
+				//如果该元素既不存在上一个元素，也不存在下一个元素， 则设置队列的头部None， 则设置队列的尾部None
+				self.first = 0;
+				self.last = 0;
+			},
+			
+			(_, 0) => {
+				//如果该元素存在上一个元素，不存在下一个元素， 则将上一个元素的下一个元素设置为None, 并设置队列的尾部为该元素的上一个元素
+				index_map.get_unchecked_mut(pre).next = 0;
+				self.last = pre;
+			},
+			(0, _) => {
+				//如果该元素不存在上一个元素，但存在下一个元素， 则将下一个元素的上一个元素设置为None, 并设置队列的头部为该元素的下一个元素
+				index_map.get_unchecked_mut(next).pre = 0;
+				self.first = next;
+			},
+			(_, _) => {
+				//如果该元素既存在上一个元素，也存在下一个元素， 则将上一个元素的下一个元素设置为本元素的下一个元素, 下一个元素的上一个元素设置为本元素的上一个元素
+				index_map.get_unchecked_mut(pre).next = next;
+				index_map.get_unchecked_mut(next).pre = pre;
+			},
+			
+		}
+		self.len -= 1;
+	}
+
+	/// Removes the first element from the Deque and returns it, or panic if Deque is empty.
+	///
+	/// # Safety
+	///
+	/// The Deque must not be empty.
+	pub unsafe fn pop_front_unchecked(&mut self, index_map: &mut C) -> T {
+		debug_assert!(self.first != 0, "pop_front_unchecked on an empty deque");
+		self.len -= 1;
+		let index = self.first;
+		let node = index_map.remove(index);
+		if self.labeled {
+			self.leave_group(index, node.group, 0, node.next);
+		}
+		self.first = node.next;
+		if self.first == 0 {
+			self.last = 0;
+		} else {
+			index_map.get_unchecked_mut(self.first).pre = 0;
+		}
+		node.elem
+	}
+	/// Removes the first element from the Deque and returns it, or None if it is empty.
+	pub fn pop_front(&mut self, index_map: &mut C) -> Option<T> {
+		if self.first == 0{
+			None
+		} else {
+			Some(unsafe { self.pop_front_unchecked(index_map) } )
+		}
+	}
+
+	/// Removes the first element from the Deque and returns it, or Empty error if it is empty.
+	pub fn pop_front_checked(&mut self, index_map: &mut C) -> Result<T, DequeError> {
+		self.pop_front(index_map).ok_or(DequeError::Empty)
+	}
+
+	/// Removes the last element from the Deque and returns it, or panic if Deque is empty.
+	///
+	/// # Safety
+	///
+	/// The Deque must not be empty.
+	pub unsafe fn pop_back_unchecked(&mut self, index_map: &mut C) -> T {
+		debug_assert!(self.last != 0, "pop_back_unchecked on an empty deque");
+		self.len -= 1;
+		let index = self.last;
+		let node = index_map.remove(index);
+		if self.labeled {
+			self.leave_group(index, node.group, node.pre, 0);
+		}
+		self.last = node.pre;
+		if self.last == 0 {
+			self.first = 0;
+		} else {
+			index_map.get_unchecked_mut(self.last).next = 0;
+		}
+		node.elem
+	}
+
+	/// Removes the last element from the Deque and returns it, or None if it is empty.
+	pub fn pop_back(&mut self, index_map: &mut C) -> Option<T> {
+		if self.last == 0 {
+			None
+		} else {
+			Some(unsafe { self.pop_back_unchecked(index_map) } )
+		}
+	}
+
+	/// Removes the last element from the Deque and returns it, or Empty error if it is empty.
+	pub fn pop_back_checked(&mut self, index_map: &mut C) -> Result<T, DequeError> {
+		self.pop_back(index_map).ok_or(DequeError::Empty)
+	}
+
+	///Removes and returns the element at index from the Deque.
+	///Panics if index is not in the index map, or belongs to another Deque.
+	pub fn remove(&mut self, index: usize, index_map: &mut C) -> T {
+		match self.remove_checked(index, index_map) {
+			Ok(elem) => elem,
+			Err(e) => panic!("{}", e),
+		}
+	}
+
+	///Removes and returns the element at index from the Deque.
+	///Returns an error if index is not in the index map, or belongs to another Deque.
+	pub fn remove_checked(&mut self, index: usize, index_map: &mut C) -> Result<T, DequeError> {
+		self.check_node(index, index_map)?;
+		unsafe { self.unlink(index, index_map) };
+		Ok(index_map.remove(index).elem)
+	}
+
+	/// Removes and returns the element of the handle, or Stale error if the element has been removed.
+	/// 这是remove和remove_checked的句柄版本，索引被复用后，旧句柄不会删除新元素
+	pub fn remove_by_handle(&mut self, handle: Handle, index_map: &mut C) -> Result<T, DequeError> {
+		self.check_handle(handle, index_map)?;
+		unsafe { self.unlink(handle.index, index_map) };
+		Ok(index_map.remove(handle.index).elem)
+	}
+
+	/// Removes and returns the element of the handle, or None if the handle is stale or belongs to another Deque.
+	/// 这是try_remove的句柄版本
+	pub fn try_remove_by_handle(&mut self, handle: Handle, index_map: &mut C) -> Option<T> {
+		self.remove_by_handle(handle, index_map).ok()
+	}
+
+	///Removes and returns the element at index from the Deque.
+	pub fn try_remove(&mut self, index: usize, index_map: &mut C) -> Option<T> {
+		match self.contains(index, index_map){
+			true => Some(self.remove(index, index_map)),
+			false => None,
+		}
+	}
+
+	/// Retains only the elements specified by the predicate, removes the others in place.
+	pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut f: F, index_map: &mut C) {
+		self.retain_mut(|elem| f(elem), index_map)
+	}
+
+	/// Retains only the elements specified by the predicate, removes the others in place.
+	/// The predicate can modify the elements.
+	pub fn retain_mut<F: FnMut(&mut T) -> bool>(&mut self, mut f: F, index_map: &mut C) {
+		let mut next = self.first;
+		while next != 0 {
+			let index = next;
+			let node = unsafe { index_map.get_unchecked_mut(index) };
+			next = node.next;
+			if !f(&mut node.elem) {
+				unsafe { self.unlink(index, index_map) };
+				index_map.remove(index);
+			}
+		}
+	}
+
+	/// Creates an iterator which uses a predicate to determine if an element should be removed.
+	/// The removed elements are yielded with their old indexs, from front to back.
+	/// If the iterator is dropped before being fully consumed, the remaining elements are retained.
+	pub fn extract_if<'a, F: FnMut(&mut T) -> bool>(&'a mut self, pred: F, index_map: &'a mut C) -> ExtractIf<'a, T, C, F> {
+		ExtractIf {
+			next: self.first,
+			deque: self,
+			index_map,
+			pred,
+		}
+	}
+
+	/// Checks the link structure of the Deque, walks from first to last.
+	/// 检查pre和next是否对称、头部的pre和尾部的next是否为0、是否有环、节点是否属于本队列以及节点个数是否等于len；正在维护顺序标签时，还检查标签是否递增、分组是否一致
+	pub fn validate(&self, index_map: &C) -> Result<(), CorruptionError> {
+		if (self.first == 0) != (self.last == 0) {
+			return Err(CorruptionError { index: self.first | self.last, kind: CorruptionKind::UnpairedEnd });
+		}
+		let mut count = 0;
+		let mut pre = 0;
+		let mut index = self.first;
+		while index != 0 {
+			let node = match index_map.get(index) {
+				Some(node) => node,
+				None => return Err(CorruptionError { index, kind: CorruptionKind::NotFound }),
+			};
+			if node.pre != pre {
+				let kind = match pre {
+					0 => CorruptionKind::FirstHasPre(node.pre),
+					_ => CorruptionKind::BrokenPre(node.pre),
+				};
+				return Err(CorruptionError { index, kind });
+			}
+			if node.owner != self.id {
+				return Err(CorruptionError { index, kind: CorruptionKind::WrongOwner(node.owner) });
+			}
+			count += 1;
+			if count > index_map.len() {
+				return Err(CorruptionError { index, kind: CorruptionKind::Cycle });
+			}
+			if index == self.last && node.next != 0 {
+				return Err(CorruptionError { index, kind: CorruptionKind::LastHasNext(node.next) });
+			}
+			if node.next == 0 && index != self.last {
+				return Err(CorruptionError { index, kind: CorruptionKind::EndsBeforeLast });
+			}
+			pre = index;
+			index = node.next;
+		}
+		if count != self.len {
+			return Err(CorruptionError { index: 0, kind: CorruptionKind::LenMismatch { len: self.len, count } });
+		}
+		match self.labeled {
+			true => self.validate_labels(index_map),
+			false => Ok(()),
+		}
+	}
+
+	// 检查顺序标签是否沿链表递增、分组是否连续且与分组记录的首尾节点和个数一致，链表结构必须已通过检查
+	fn validate_labels(&self, index_map: &C) -> Result<(), CorruptionError> {
+		let mut group_len = 0;
+		let mut pre = 0;
+		let mut index = self.first;
+		while index != 0 {
+			let node = unsafe { index_map.get_unchecked(index) };
+			let group = match self.groups.get(node.group as usize) {
+				Some(group) => group,
+				None => return Err(CorruptionError { index, kind: CorruptionKind::BadGroup(node.group) }),
+			};
+			let group_start = match pre {
+				0 => true,
+				_ => {
+					let pre_node = unsafe { index_map.get_unchecked(pre) };
+					if (group.label, node.label) <= (self.groups[pre_node.group as usize].label, pre_node.label) {
+						return Err(CorruptionError { index, kind: CorruptionKind::LabelOrder { group: group.label, label: node.label } });
+					}
+					pre_node.group != node.group
+				},
+			};
+			if group_start {
+				if group.first != index {
+					return Err(CorruptionError { index, kind: CorruptionKind::BadGroup(node.group) });
+				}
+				group_len = 0;
+			}
+			group_len += 1;
+			let group_end = node.next == 0 || unsafe { index_map.get_unchecked(node.next).group } != node.group;
+			if group_end && (group.last != index || group.len != group_len) {
+				return Err(CorruptionError { index, kind: CorruptionKind::BadGroup(node.group) });
+			}
+			pre = index;
+			index = node.next;
+		}
+		Ok(())
+	}
+
+	/// Copies the elements of the Deque from src_map into dst_map, return the new Deque and the mapping from old indexs to new indexs.
+	/// 新队列的节点有新的标识和代数，原队列的句柄不能用于新队列
+	/// Deque没有实现Clone：只复制头部的队列会与原队列共享节点，修改其中一个会破坏另一个，需要复制队列时请使用本方法
+	pub fn clone_into(&self, src_map: &C, dst_map: &mut C) -> (Self, HashMap<usize, usize>) where T: Clone {
+		let mut deque = Deque::new();
+		let mut indexs = HashMap::with_capacity(self.len);
+		let mut next = self.first;
+		while next != 0 {
+			let node = unsafe { src_map.get_unchecked(next) };
+			indexs.insert(next, deque.push_back(node.elem.clone(), dst_map));
+			next = node.next;
+		}
+		(deque, indexs)
+	}
+
+	/// Rotates the Deque n places to the left, the first n elements are moved to the back, n is taken modulo len.
+	/// 只修改链接，元素索引不变，时间复杂度O(min(n, len - n))
+	pub fn rotate_left(&mut self, n: usize, index_map: &mut C) {
+		if self.len < 2 {
+			return;
+		}
+		let n = n % self.len;
+		if n == 0 {
+			return;
+		}
+		// 旋转后的尾部是原队列中第n-1个节点
+		let new_last = self.nth(n - 1, index_map).unwrap();
+		let (old_first, old_last) = (self.first, self.last);
+		unsafe {
+			// 新的头部和尾部不能在同一个分组中
+			let new_first = index_map.get_unchecked(new_last).next;
+			if self.labeled {
+				self.cut_group(new_first, index_map);
+			}
+			index_map.get_unchecked_mut(new_last).next = 0;
+			index_map.get_unchecked_mut(new_first).pre = 0;
+			index_map.get_unchecked_mut(old_last).next = old_first;
+			index_map.get_unchecked_mut(old_first).pre = old_last;
+			self.first = new_first;
+			self.last = new_last;
+			// 原尾部与原头部的连接处标签无序，调整被移动的节点较少的一侧
+			if self.labeled {
+				let forward = n <= self.len - n;
+				let (pre, next) = (index_map.get_unchecked(old_last).group, index_map.get_unchecked(old_first).group);
+				self.fix_group_labels(pre, next, forward, index_map);
+			}
+		}
+	}
+
+	/// Rotates the Deque n places to the right, the last n elements are moved to the front, n is taken modulo len.
+	/// 只修改链接，元素索引不变，时间复杂度O(min(n, len - n))
+	pub fn rotate_right(&mut self, n: usize, index_map: &mut C) {
+		if self.len < 2 {
+			return;
+		}
+		let n = n % self.len;
+		self.rotate_left(self.len - n, index_map);
+	}
+
+	/// Reverses the order of the elements in place, indexs of the elements are unchanged.
+	pub fn reverse(&mut self, index_map: &mut C) {
+		let mut next = self.first;
+		while next != 0 {
+			let node = unsafe { index_map.get_unchecked_mut(next) };
+			std::mem::swap(&mut node.pre, &mut node.next);
+			next = node.pre;
+		}
+		std::mem::swap(&mut self.first, &mut self.last);
+		self.drop_labels();
+	}
+
+	/// Swaps the positions of the elements at a and b, indexs of the elements are unchanged.
+	pub fn swap(&mut self, a: usize, b: usize, index_map: &mut C) -> Result<(), DequeError> {
+		self.check_node(a, index_map)?;
+		self.check_node(b, index_map)?;
+		if a == b {
+			return Ok(());
+		}
+		unsafe {
+			let a_next = index_map.get_unchecked(a).next;
+			let b_next = index_map.get_unchecked(b).next;
+			if a_next == b {
+				self.unlink(a, index_map);
+				self.link_after(a, b, index_map);
+			} else if b_next == a {
+				self.unlink(b, index_map);
+				self.link_after(b, a, index_map);
+			} else {
+				// 不相邻时，先将a移动到b之前，再将b移动到a原来的位置
+				self.unlink(a, index_map);
+				self.link_before(a, b, index_map);
+				self.unlink(b, index_map);
+				match a_next {
+					0 => self.link_back(b, index_map),
+					_ => self.link_before(b, a_next, index_map),
+				}
+			}
+		}
+		Ok(())
+	}
+
+	/// Sorts the Deque with a stable sort, only pre and next of the nodes are rewritten, indexs of the elements are unchanged.
+	pub fn sort(&mut self, index_map: &mut C) where T: Ord {
+		self.sort_by(|a, b| a.cmp(b), index_map)
+	}
+
+	/// Sorts the Deque with a key extraction function, the sort is stable and indexs of the elements are unchanged.
+	pub fn sort_by_key<K: Ord, F: FnMut(&T) -> K>(&mut self, mut f: F, index_map: &mut C) {
+		self.sort_by(|a, b| f(a).cmp(&f(b)), index_map)
+	}
+
+	/// Sorts the Deque with a comparator function, the sort is stable and indexs of the elements are unchanged.
+	/// If the comparator panics, the Deque is left unchanged.
+	/// 先对节点索引排序，再按排序结果重新链接节点，时间复杂度O(n*log(n))，需要O(n)的额外内存
+	/// 比较期间不修改链表，因此比较函数panic时队列保持原样
+	pub fn sort_by<F: FnMut(&T, &T) -> COrdering>(&mut self, mut compare: F, index_map: &mut C) {
+		if self.len < 2 {
+			return;
+		}
+		let mut indexs = Vec::with_capacity(self.len);
+		let mut next = self.first;
+		while next != 0 {
+			indexs.push(next);
+			next = unsafe { index_map.get_unchecked(next).next };
+		}
+		{
+			let map: &C = index_map;
+			indexs.sort_by(|a, b| unsafe { compare(&map.get_unchecked(*a).elem, &map.get_unchecked(*b).elem) });
+		}
+
+		let mut pre = 0;
+		for (i, index) in indexs.iter().enumerate() {
+			let node = unsafe { index_map.get_unchecked_mut(*index) };
+			node.pre = pre;
+			node.next = indexs.get(i + 1).copied().unwrap_or(0);
+			pre = *index;
+		}
+		self.first = indexs[0];
+		self.last = pre;
+		self.drop_labels();
+	}
+
+	/// Inserts an element into a Deque sorted by compare, return a index.
+	/// The element is inserted after all elements equal to it, the search starts from the end given by from.
+	pub fn insert_sorted_by<F: FnMut(&T, &T) -> COrdering>(&mut self, elem: T, mut compare: F, from: SearchFrom, index_map: &mut C) -> usize {
+		match from {
+			SearchFrom::Back => {
+				// 找到最后一个不大于elem的节点，插入到其之后
+				let mut pre = self.last;
+				while pre != 0 {
+					let node = unsafe { index_map.get_unchecked(pre) };
+					if compare(&node.elem, &elem) != COrdering::Greater {
+						break;
+					}
+					pre = node.pre;
+				}
+				match pre {
+					0 => self.push_front(elem, index_map),
+					pre => unsafe { self.insert_after_unchecked(elem, pre, index_map) },
+				}
+			},
+			SearchFrom::Front => {
+				// 找到第一个大于elem的节点，插入到其之前
+				let mut next = self.first;
+				while next != 0 {
+					let node = unsafe { index_map.get_unchecked(next) };
+					if compare(&node.elem, &elem) == COrdering::Greater {
+						break;
+					}
+					next = node.next;
+				}
+				match next {
+					0 => self.push_back(elem, index_map),
+					next => unsafe { self.insert_before_unchecked(elem, next, index_map) },
+				}
+			},
+		}
+	}
+
+	/// Creates a draining iterator that removes the elements and yields them from front to back.
+	/// The remaining elements are removed when the iterator is dropped.
+	pub fn drain<'a>(&'a mut self, index_map: &'a mut C) -> Drain<'a, T, C> {
+		Drain {
+			deque: self,
+			index_map,
+		}
+	}
+
+	//clear Deque
+	pub fn clear(&mut self, index_map: &mut C) {
+		loop {
+			if self.first == 0 {
+				self.last = 0;
+				break;
+			}
+			let node = index_map.remove(self.first);
+			self.first = node.next;
+		}
+		self.len = 0;
+		self.groups.clear();
+		self.free_groups.clear();
+	}
+
+	//clear Deque
+	pub fn len(&self) -> usize {
+		self.len
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	pub fn iter<'a>(&self, container: &'a C) -> Iter<'a, T, C> {
+		Iter{
+			next: self.first,
+			back: self.last,
+			len: self.len,
+			container,
+			mark: PhantomData,
+		}
+	}
+
+	pub fn iter_mut<'a>(&self, container: &'a mut C) -> IterMut<'a, T, C> {
+		IterMut{
+			next: self.first,
+			back: self.last,
+			len: self.len,
+			container,
+			mark: PhantomData,
+		}
+	}
+
+	/// Provides a cursor at the front element, or the "ghost" non-element if the Deque is empty.
+	pub fn cursor_front<'a>(&'a self, index_map: &'a C) -> Cursor<'a, T, C> {
+		Cursor {
+			index: 0,
+			current: self.first,
+			deque: self,
+			index_map,
+		}
+	}
+
+	/// Provides a cursor at the back element, or the "ghost" non-element if the Deque is empty.
+	pub fn cursor_back<'a>(&'a self, index_map: &'a C) -> Cursor<'a, T, C> {
+		Cursor {
+			index: self.len.saturating_sub(1),
+			current: self.last,
+			deque: self,
+			index_map,
+		}
+	}
+
+	/// Provides a cursor with editing operations at the front element, or the "ghost" non-element if the Deque is empty.
+	pub fn cursor_front_mut<'a>(&'a mut self, index_map: &'a mut C) -> CursorMut<'a, T, C> {
+		CursorMut {
+			index: 0,
+			current: self.first,
+			deque: self,
+			index_map,
+		}
+	}
+
+	/// Provides a cursor with editing operations at the back element, or the "ghost" non-element if the Deque is empty.
+	pub fn cursor_back_mut<'a>(&'a mut self, index_map: &'a mut C) -> CursorMut<'a, T, C> {
+		CursorMut {
+			index: self.len.saturating_sub(1),
+			current: self.last,
+			deque: self,
+			index_map,
+		}
+	}
+
+	/// Moves all elements of other to the back of self, leaving other empty.
+	/// Both Deques must share the same index map, indexs of the elements are unchanged.
+	/// 只重新标记较短一方的节点的所属队列和顺序标签分组，因此开销为O(min(self.len, other.len))；只有一方调用过precedes时，还需要为另一方分配顺序标签
+	/// 如果other更长，两个队列会交换标识，因此拼接后self.id()和other.id()可能改变
+	pub fn append(&mut self, other: &mut Self, index_map: &mut C) {
+		if other.first == 0 {
+			return;
+		}
+		let junction = self.last;
+		let self_shorter = other.len > self.len;
+		// 只有一方在维护标签时，为另一方分配标签，其开销均摊到该方此前的插入上
+		let labeled = self.labeled || other.labeled;
+		if labeled && !self.labeled {
+			self.regroup_all(index_map);
+		} else if labeled && !other.labeled {
+			other.regroup_all(index_map);
+		}
+		unsafe {
+			if self_shorter {
+				// 较长的other的节点保留原来的分组，self的节点转移到other的分组列表中
+				std::mem::swap(&mut self.id, &mut other.id);
+				std::mem::swap(&mut self.groups, &mut other.groups);
+				std::mem::swap(&mut self.free_groups, &mut other.free_groups);
+				if junction != 0 {
+					self.retag(self.first, index_map);
+					if labeled {
+						other.move_groups(self.first, self.last, self, index_map);
+					}
+				}
+			} else {
+				self.retag(other.first, index_map);
+				if labeled {
+					other.move_groups(other.first, other.last, self, index_map);
+				}
+			}
+		}
+		if self.last == 0 {
+			self.first = other.first;
+		} else {
+			unsafe {
+				index_map.get_unchecked_mut(self.last).next = other.first;
+				index_map.get_unchecked_mut(other.first).pre = self.last;
+			}
+		}
+		self.last = other.last;
+		self.len += other.len;
+		if junction != 0 && labeled {
+			unsafe {
+				let (pre, next) = (index_map.get_unchecked(junction).group, index_map.get_unchecked(other.first).group);
+				self.fix_group_labels(pre, next, !self_shorter, index_map);
+			}
+		}
+		self.labeled = labeled;
+		other.first = 0;
+		other.last = 0;
+		other.len = 0;
+		other.groups.clear();
+		other.free_groups.clear();
+	}
+
+	/// Splits the Deque into two at the element at index, return a new Deque containing the element at index and everything after it.
+	/// The new Deque shares the index map with self, indexs of the elements are unchanged.
+	/// 需要遍历被分离的元素以得到其个数，并将其重新标记为属于新队列，因此开销为O(被分离的元素个数)
+	pub fn split_off(&mut self, index: usize, index_map: &mut C) -> Result<Self, DequeError> {
+		self.check_node(index, index_map)?;
+		let mut count = 0;
+		let mut next = index;
+		while next != 0 {
+			count += 1;
+			next = unsafe { index_map.get_unchecked(next).next };
+		}
+		let pre = unsafe { index_map.get_unchecked(index).pre };
+		Ok(self.detach_after(pre, count, index_map))
+	}
+
+	// 将node之后的count个节点分离为一个新的队列，node为0表示分离整个队列
+	fn detach_after(&mut self, node: usize, count: usize, index_map: &mut C) -> Self {
+		let first = match node {
+			0 => self.first,
+			_ => unsafe { replace(&mut index_map.get_unchecked_mut(node).next, 0) },
+		};
+		let mut other = Deque::new();
+		if first == 0 {
+			return other;
+		}
+		unsafe {
+			if self.labeled {
+				other.labeled = true;
+				self.move_groups(first, self.last, &mut other, index_map);
+			}
+			index_map.get_unchecked_mut(first).pre = 0;
+		}
+		other.first = first;
+		other.last = self.last;
+		other.len = count;
+
+		self.last = node;
+		if node == 0 {
+			self.first = 0;
+		}
+		self.len -= count;
+		other.retag(other.first, index_map);
+		other
+	}
+
+	// 将node之前的count个节点分离为一个新的队列，node为0表示分离整个队列
+	fn detach_before(&mut self, node: usize, count: usize, index_map: &mut C) -> Self {
+		let last = match node {
+			0 => self.last,
+			_ => unsafe { replace(&mut index_map.get_unchecked_mut(node).pre, 0) },
+		};
+		let mut other = Deque::new();
+		if last == 0 {
+			return other;
+		}
+		unsafe {
+			if self.labeled {
+				other.labeled = true;
+				self.move_groups(self.first, last, &mut other, index_map);
+			}
+			index_map.get_unchecked_mut(last).next = 0;
+		}
+		other.first = self.first;
+		other.last = last;
+		other.len = count;
+
+		self.first = node;
+		if node == 0 {
+			self.last = 0;
+		}
+		self.len -= count;
+		other.retag(other.first, index_map);
+		other
+	}
+
+	// 为索引工厂的逐节点副本（索引与原索引工厂相同）创建队列，新队列有新的标识，并将副本中的节点重新标记为属于新队列
+	// dst_map不能是本队列使用的索引工厂，否则两个队列会共享节点
+	pub(crate) fn clone_header(&self, dst_map: &mut C) -> Self {
+		let deque = Deque {
+			id: next_nonzero(&NEXT_ID),
+			first: self.first,
+			last: self.last,
+			len: self.len,
+			groups: self.groups.clone(),
+			free_groups: self.free_groups.clone(),
+			labeled: self.labeled,
+			mark: PhantomData,
+		};
+		deque.retag(deque.first, dst_map);
+		deque
+	}
+
+	// 将从first开始到链表尾部的节点都标记为属于本队列
+	fn retag(&self, first: usize, index_map: &mut C) {
+		let mut next = first;
+		while next != 0 {
+			let node = unsafe { index_map.get_unchecked_mut(next) };
+			node.owner = self.id;
+			next = node.next;
+		}
+	}
+
+}
+
+
+pub struct Iter<'a, T: 'a, C: 'a + IndexMap<Node<T>>> {
+	next: usize,
+	back: usize,
+	len: usize,
+	container: &'a C,
+	mark: PhantomData<T>
+}
+
+impl<'a, T, C: IndexMap<Node<T>>> Iterator for Iter<'a, T, C> {
+	type Item = &'a T;
+
+	fn next(&mut self) -> Option<&'a T> {
+		if self.len == 0 {
+			return None;
+		}
+		
+		let node = unsafe{self.container.get_unchecked(self.next)};
+		self.next = node.next;
+		self.len -= 1;
+		Some(&node.elem)
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(self.len, Some(self.len))
+	}
+}
+
+impl<'a, T, C: IndexMap<Node<T>>> DoubleEndedIterator for Iter<'a, T, C> {
+	fn next_back(&mut self) -> Option<&'a T> {
+		if self.len == 0 {
+			return None;
+		}
+
+		let node = unsafe{self.container.get_unchecked(self.back)};
+		self.back = node.pre;
+		self.len -= 1;
+		Some(&node.elem)
+	}
+}
+
+impl<'a, T, C: IndexMap<Node<T>>> ExactSizeIterator for Iter<'a, T, C> {}
+
+pub struct IterMut<'a, T: 'a, C: 'a + IndexMap<Node<T>>> {
+	next: usize,
+	back: usize,
+	len: usize,
+	container: &'a mut C,
+	mark: PhantomData<T>
+}
+
+impl<'a, T, C: IndexMap<Node<T>>> Iterator for IterMut<'a, T, C> {
+	type Item = &'a mut T;
+
+	fn next(&mut self) -> Option<&'a mut T> {
+		if self.len == 0 {
+			return None;
+		}
+
+		// 每个节点只会被返回一次，因此返回的可变引用之间不会重叠
+		let node = unsafe{&mut *(self.container.get_unchecked_mut(self.next) as *mut Node<T>)};
+		self.next = node.next;
+		self.len -= 1;
+		Some(&mut node.elem)
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(self.len, Some(self.len))
 	}
 }
 
-impl<T, C: IndexMap<Node<T>>> Deque<T, C> {
-	pub fn new() -> Self {
-		Self {
-			first: 0,
-			last: 0,
-			len: 0,
-			mark: PhantomData,
+impl<'a, T, C: IndexMap<Node<T>>> DoubleEndedIterator for IterMut<'a, T, C> {
+	fn next_back(&mut self) -> Option<&'a mut T> {
+		if self.len == 0 {
+			return None;
 		}
+
+		let node = unsafe{&mut *(self.container.get_unchecked_mut(self.back) as *mut Node<T>)};
+		self.back = node.pre;
+		self.len -= 1;
+		Some(&mut node.elem)
 	}
+}
 
-	pub fn get_first(&self) -> usize {
-		self.first
+impl<'a, T, C: IndexMap<Node<T>>> ExactSizeIterator for IterMut<'a, T, C> {}
+
+pub struct Drain<'a, T: 'a, C: 'a + IndexMap<Node<T>>> {
+	deque: &'a mut Deque<T, C>,
+	index_map: &'a mut C,
+}
+
+impl<'a, T, C: IndexMap<Node<T>>> Iterator for Drain<'a, T, C> {
+	type Item = T;
+
+	fn next(&mut self) -> Option<T> {
+		self.deque.pop_front(self.index_map)
 	}
 
-	pub fn get_last(&self) -> usize {
-		self.last
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(self.deque.len, Some(self.deque.len))
 	}
+}
 
-	/// Append an element to the Deque. return a index
-	pub fn push_back(&mut self, elem: T, index_map: &mut C) -> usize {
-		self.len += 1;
-		if self.last == 0 {
-			let index = index_map.insert(Node::new(elem, 0, 0));
-			self.last = index;
-			self.first = index;
-			index
-		}else {
-			let index = index_map.insert(Node::new(elem, self.last, 0));
-			unsafe{index_map.get_unchecked_mut(self.last).next = index;}
-			self.last = index;
-			index
-		}
+impl<'a, T, C: IndexMap<Node<T>>> DoubleEndedIterator for Drain<'a, T, C> {
+	fn next_back(&mut self) -> Option<T> {
+		self.deque.pop_back(self.index_map)
 	}
+}
 
-	/// Prepend an element to the Deque. return a index
-	pub fn push_front(&mut self, elem: T, index_map: &mut C) -> usize{
-		self.len += 1;
-		if self.first == 0 {
-			let index = index_map.insert(Node::new(elem, 0, 0));
-			self.last = index;
-			self.first = index;
-			index
-		}else {
-			let index = index_map.insert(Node::new(elem, 0, self.first));
-			unsafe{index_map.get_unchecked_mut(self.first).pre = index;}
-			self.first = index;
-			index
-		}
+impl<'a, T, C: IndexMap<Node<T>>> ExactSizeIterator for Drain<'a, T, C> {}
+
+impl<'a, T, C: IndexMap<Node<T>>> Drop for Drain<'a, T, C> {
+	fn drop(&mut self) {
+		self.deque.clear(self.index_map);
 	}
+}
 
-	/// Append an element to the Deque. return a index
-	pub unsafe fn push_to_back(&mut self, elem: T, index: usize, index_map: &mut C) -> usize{
-		self.len += 1;
-		let i = index_map.insert(Node::new(elem, index, 0));
+pub struct ExtractIf<'a, T: 'a, C: 'a + IndexMap<Node<T>>, F: FnMut(&mut T) -> bool> {
+	next: usize,
+	deque: &'a mut Deque<T, C>,
+	index_map: &'a mut C,
+	pred: F,
+}
 
-		let next = {
-			let e = index_map.get_unchecked_mut(index);
-			replace(&mut e.next, i)
-		};
+impl<'a, T, C: IndexMap<Node<T>>, F: FnMut(&mut T) -> bool> Iterator for ExtractIf<'a, T, C, F> {
+	type Item = (usize, T);
 
-		if next == 0 {
-			self.last = i;
-		} else {
-			index_map.get_unchecked_mut(next).pre = i;
-			index_map.get_unchecked_mut(i).next = next;
+	fn next(&mut self) -> Option<(usize, T)> {
+		while self.next != 0 {
+			let index = self.next;
+			let node = unsafe { self.index_map.get_unchecked_mut(index) };
+			self.next = node.next;
+			if (self.pred)(&mut node.elem) {
+				unsafe { self.deque.unlink(index, self.index_map) };
+				return Some((index, self.index_map.remove(index).elem));
+			}
 		}
+		None
+	}
 
-		i
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(0, Some(self.deque.len))
 	}
+}
 
-	/// Prepend an element to the Deque. return a index
-	pub unsafe fn push_to_front(&mut self, elem: T, index: usize, index_map: &mut C) -> usize{
-		self.len += 1;
-		let i = index_map.insert(Node::new(elem, 0, index));
+/// 队列上的只读游标，与标准库LinkedList的Cursor类似
+/// 游标可以指向队列中的任意元素，也可以指向位于队列尾部和头部之间的"ghost"非元素
+pub struct Cursor<'a, T: 'a, C: 'a + IndexMap<Node<T>>> {
+	index: usize,
+	current: usize,
+	deque: &'a Deque<T, C>,
+	index_map: &'a C,
+}
 
-		let pre = {
-			let e = index_map.get_unchecked_mut(index);
-			replace(&mut e.pre, i)
-		};
+impl<'a, T, C: IndexMap<Node<T>>> Cursor<'a, T, C> {
+	/// Returns the position of the cursor in the Deque, or None if the cursor is at the "ghost" non-element.
+	pub fn index(&self) -> Option<usize> {
+		match self.current {
+			0 => None,
+			_ => Some(self.index),
+		}
+	}
 
-		if pre == 0 {
-			self.first = i;
-		} else {
-			index_map.get_unchecked_mut(pre).next = i;
-			index_map.get_unchecked_mut(i).pre = pre;
+	/// Returns the index (in the index map) of the current element, or None at the "ghost" non-element.
+	pub fn node(&self) -> Option<usize> {
+		match self.current {
+			0 => None,
+			i => Some(i),
 		}
+	}
 
-		i
+	/// Moves the cursor to the next element, the "ghost" non-element wraps to the front.
+	pub fn move_next(&mut self) {
+		match self.current {
+			0 => {
+				self.current = self.deque.first;
+				self.index = 0;
+			},
+			i => {
+				self.current = unsafe { self.index_map.get_unchecked(i).next };
+				self.index += 1;
+			},
+		}
 	}
-	/// Removes the first element from the Deque and returns it, or panic if Deque is empty.
-	pub unsafe fn pop_front_unchecked(&mut self, index_map: &mut C) -> T {
-		self.len -= 1;
-		let node = index_map.remove(self.first);
-		self.first = node.next;
-		if self.first == 0 {
-			self.last = 0;
-		} else {
-			index_map.get_unchecked_mut(self.first).pre = 0;
+
+	/// Moves the cursor to the previous element, the "ghost" non-element wraps to the back.
+	pub fn move_prev(&mut self) {
+		match self.current {
+			0 => {
+				self.current = self.deque.last;
+				self.index = self.deque.len.saturating_sub(1);
+			},
+			i => {
+				self.current = unsafe { self.index_map.get_unchecked(i).pre };
+				self.index = self.index.checked_sub(1).unwrap_or(self.deque.len);
+			},
 		}
-		node.elem
 	}
-	/// Removes the first element from the Deque and returns it, or None if it is empty.
-	pub fn pop_front(&mut self, index_map: &mut C) -> Option<T> {
-		if self.first == 0{
-			None
-		} else {
-			Some(unsafe { self.pop_front_unchecked(index_map) } )
+
+	/// Returns a reference to the current element, or None at the "ghost" non-element.
+	pub fn current(&self) -> Option<&'a T> {
+		match self.current {
+			0 => None,
+			i => Some(unsafe { &self.index_map.get_unchecked(i).elem }),
 		}
 	}
 
-	/// Removes the last element from the Deque and returns it, or panic if Deque is empty.
-	pub unsafe fn pop_back_unchecked(&mut self, index_map: &mut C) -> T {
-		self.len -= 1;
-		let node = index_map.remove(self.last);
-		self.last = node.pre;
-		if self.last == 0 {
-			self.first = 0;
-		} else {
-			index_map.get_unchecked_mut(self.last).next = 0;
+	/// Returns a reference to the next element without moving the cursor.
+	pub fn peek_next(&self) -> Option<&'a T> {
+		let next = match self.current {
+			0 => self.deque.first,
+			i => unsafe { self.index_map.get_unchecked(i).next },
+		};
+		match next {
+			0 => None,
+			i => Some(unsafe { &self.index_map.get_unchecked(i).elem }),
 		}
-		node.elem
 	}
 
-	/// Removes the last element from the Deque and returns it, or None if it is empty.
-	pub fn pop_back(&mut self, index_map: &mut C) -> Option<T> {
-		if self.last == 0 {
-			None
-		} else {
-			Some(unsafe { self.pop_back_unchecked(index_map) } )
+	/// Returns a reference to the previous element without moving the cursor.
+	pub fn peek_prev(&self) -> Option<&'a T> {
+		let pre = match self.current {
+			0 => self.deque.last,
+			i => unsafe { self.index_map.get_unchecked(i).pre },
+		};
+		match pre {
+			0 => None,
+			i => Some(unsafe { &self.index_map.get_unchecked(i).elem }),
+		}
+	}
+}
+
+/// 队列上可编辑的游标，与标准库LinkedList的CursorMut类似
+/// 通过游标可以在遍历的同时，安全的在队列中间插入或删除元素
+pub struct CursorMut<'a, T: 'a, C: 'a + IndexMap<Node<T>>> {
+	index: usize,
+	current: usize,
+	deque: &'a mut Deque<T, C>,
+	index_map: &'a mut C,
+}
+
+impl<'a, T, C: IndexMap<Node<T>>> CursorMut<'a, T, C> {
+	/// Returns the position of the cursor in the Deque, or None if the cursor is at the "ghost" non-element.
+	pub fn index(&self) -> Option<usize> {
+		match self.current {
+			0 => None,
+			_ => Some(self.index),
 		}
 	}
 
-	///Removes and returns the element at index from the Deque.
-	pub fn remove(&mut self, index: usize, index_map: &mut C) -> T {
-		let node = index_map.remove(index);
-		match (node.pre, node.next) {
-			(0, 0) => {
-				//如果该元素既不存在上一个元素，也不存在下一个元素， 则设置队列的头部None， 则设置队列的尾部None
-				self.first = 0;
-				self.last = 0;
+	/// Returns the index (in the index map) of the current element, or None at the "ghost" non-element.
+	pub fn node(&self) -> Option<usize> {
+		match self.current {
+			0 => None,
+			i => Some(i),
+		}
+	}
+
+	/// Moves the cursor to the next element, the "ghost" non-element wraps to the front.
+	pub fn move_next(&mut self) {
+		match self.current {
+			0 => {
+				self.current = self.deque.first;
+				self.index = 0;
 			},
-			
-			(_, 0) => {
-				//如果该元素存在上一个元素，不存在下一个元素， 则将上一个元素的下一个元素设置为None, 并设置队列的尾部为该元素的上一个元素
-				unsafe{ index_map.get_unchecked_mut(node.pre).next = 0};
-				self.last = node.pre;
+			i => {
+				self.current = unsafe { self.index_map.get_unchecked(i).next };
+				self.index += 1;
 			},
-			(0, _) => {
-				//如果该元素不存在上一个元素，但存在下一个元素， 则将下一个元素的上一个元素设置为None, 并设置队列的头部为该元素的下一个元素
-				unsafe{ index_map.get_unchecked_mut(node.next).pre = 0};
-				self.first = node.next;
+		}
+	}
+
+	/// Moves the cursor to the previous element, the "ghost" non-element wraps to the back.
+	pub fn move_prev(&mut self) {
+		match self.current {
+			0 => {
+				self.current = self.deque.last;
+				self.index = self.deque.len.saturating_sub(1);
 			},
-			(_, _) => {
-				//如果该元素既存在上一个元素，也存在下一个元素， 则将上一个元素的下一个元素设置为本元素的下一个元素, 下一个元素的上一个元素设置为本元素的上一个元素
-				unsafe{ index_map.get_unchecked_mut(node.pre).next = node.next};
-				unsafe{ index_map.get_unchecked_mut(node.next).pre = node.pre};
+			i => {
+				self.current = unsafe { self.index_map.get_unchecked(i).pre };
+				self.index = self.index.checked_sub(1).unwrap_or(self.deque.len);
 			},
-			
 		}
-		self.len -= 1;
-		node.elem
 	}
 
-	///Removes and returns the element at index from the Deque.
-	pub fn try_remove(&mut self, index: usize, index_map: &mut C) -> Option<T> {
-		match index_map.contains(index){
-			true => Some(self.remove(index, index_map)),
-			false => None,
+	/// Returns a mutable reference to the current element, or None at the "ghost" non-element.
+	pub fn current(&mut self) -> Option<&mut T> {
+		match self.current {
+			0 => None,
+			i => Some(unsafe { &mut self.index_map.get_unchecked_mut(i).elem }),
 		}
 	}
 
-	//clear Deque
-	pub fn clear(&mut self, index_map: &mut C) {
-		loop {
-			if self.first == 0 {
-				self.last = 0;
-				break;
-			}
-			let node = index_map.remove(self.first);
-			self.first = node.next;
+	/// Returns a mutable reference to the next element without moving the cursor.
+	pub fn peek_next(&mut self) -> Option<&mut T> {
+		let next = match self.current {
+			0 => self.deque.first,
+			i => unsafe { self.index_map.get_unchecked(i).next },
+		};
+		match next {
+			0 => None,
+			i => Some(unsafe { &mut self.index_map.get_unchecked_mut(i).elem }),
 		}
-		self.len = 0;
 	}
 
-	//clear Deque
-	pub fn len(&self) -> usize {
-		self.len
+	/// Returns a mutable reference to the previous element without moving the cursor.
+	pub fn peek_prev(&mut self) -> Option<&mut T> {
+		let pre = match self.current {
+			0 => self.deque.last,
+			i => unsafe { self.index_map.get_unchecked(i).pre },
+		};
+		match pre {
+			0 => None,
+			i => Some(unsafe { &mut self.index_map.get_unchecked_mut(i).elem }),
+		}
 	}
 
-	pub fn iter<'a>(&self, container: &'a C) -> Iter<'a, T, C> {
-		Iter{
-			next: self.first,
-			container: container,
-			mark: PhantomData,
+	/// Returns a read-only cursor pointing to the current element.
+	pub fn as_cursor(&self) -> Cursor<'_, T, C> {
+		Cursor {
+			index: self.index,
+			current: self.current,
+			deque: self.deque,
+			index_map: self.index_map,
 		}
 	}
 
-}
-
-impl<T, C: IndexMap<Node<T>>> Clone for Deque<T, C>{
-	fn clone(&self) -> Deque<T, C>{
-		Deque {
-			first: self.first,
-			last: self.last,
-			len: self.len,
-			mark: PhantomData
+	/// Inserts a new element after the current one, return the index of the new element.
+	/// If the cursor is at the "ghost" non-element, the new element is inserted at the front of the Deque.
+	pub fn insert_after(&mut self, elem: T) -> usize {
+		match self.current {
+			0 => {
+				let i = self.deque.push_front(elem, self.index_map);
+				self.index = self.deque.len;
+				i
+			},
+			// current是本队列的节点
+			current => unsafe { self.deque.insert_after_unchecked(elem, current, self.index_map) },
 		}
 	}
-}
-
 
-pub struct Iter<'a, T: 'a, C: 'a + IndexMap<Node<T>>> {
-	next: usize,
-	container: &'a C,
-	mark: PhantomData<T>
-}
+	/// Inserts a new element before the current one, return the index of the new element.
+	/// If the cursor is at the "ghost" non-element, the new element is inserted at the back of the Deque.
+	pub fn insert_before(&mut self, elem: T) -> usize {
+		let i = match self.current {
+			0 => self.deque.push_back(elem, self.index_map),
+			current => unsafe { self.deque.insert_before_unchecked(elem, current, self.index_map) },
+		};
+		self.index += 1;
+		i
+	}
 
-impl<'a, T, C: IndexMap<Node<T>>> Iterator for Iter<'a, T, C> {
-	type Item = &'a T;
+	/// Removes the current element and returns it, the cursor is moved to the next element.
+	/// If the cursor is at the "ghost" non-element, nothing is removed and None is returned.
+	pub fn remove_current(&mut self) -> Option<T> {
+		match self.current {
+			0 => None,
+			current => {
+				self.current = unsafe { self.index_map.get_unchecked(current).next };
+				Some(self.deque.remove(current, self.index_map))
+			},
+		}
+	}
 
-	fn next(&mut self) -> Option<&'a T> {
-		if self.next == 0 {
-			return None;
+	/// Splits the Deque into two after the current element, return a new Deque consisting of everything after the cursor.
+	/// The new Deque shares the index map with the original one.
+	/// If the cursor is at the "ghost" non-element, the entire contents of the Deque are moved.
+	/// 被分离的节点需要重新标记为属于新队列，开销为O(被分离的元素个数)
+	pub fn split_after(&mut self) -> Deque<T, C> {
+		let count = match self.current {
+			0 => self.deque.len,
+			_ => self.deque.len - self.index - 1,
+		};
+		let other = self.deque.detach_after(self.current, count, self.index_map);
+		if self.current == 0 {
+			self.index = 0;
 		}
-		
-		let node = unsafe{self.container.get_unchecked(self.next)};
-		self.next = node.next;
-		Some(&node.elem)
+		other
+	}
+
+	/// Splits the Deque into two before the current element, return a new Deque consisting of everything before the cursor.
+	/// The new Deque shares the index map with the original one.
+	/// If the cursor is at the "ghost" non-element, the entire contents of the Deque are moved.
+	/// 被分离的节点需要重新标记为属于新队列，开销为O(被分离的元素个数)
+	pub fn split_before(&mut self) -> Deque<T, C> {
+		let count = match self.current {
+			0 => self.deque.len,
+			_ => self.index,
+		};
+		let other = self.deque.detach_before(self.current, count, self.index_map);
+		self.index = 0;
+		other
 	}
 }
 
 impl<T, C: IndexMap<Node<T>>> Debug for Deque<T, C> {
 	fn fmt(&self, f: &mut Formatter) -> FResult {
 		f.debug_struct("Deque")
+			.field("id", &self.id)
 			.field("first", &self.first)
 			.field("last", &self.last)
 			.finish()
@@ -271,14 +2156,36 @@ pub struct Node<T>{
 	pub elem: T,
 	pub next: usize,
 	pub pre: usize,
+	pub gen: u32,
+	pub owner: u32,
+	pub group: u32,
+	pub label: u32,
 }
 
 impl<T> Node<T>{
-	fn new(elem: T, pre: usize, next: usize) -> Node<T>{
+	fn new(elem: T, pre: usize, next: usize, owner: u32) -> Node<T>{
 		Node{
 			elem,
 			pre,
 			next,
+			gen: 0,
+			owner,
+			group: 0,
+			label: 0,
+		}
+	}
+}
+
+impl<T: Clone> Clone for Node<T> {
+	fn clone(&self) -> Node<T> {
+		Node{
+			elem: self.elem.clone(),
+			next: self.next,
+			pre: self.pre,
+			gen: self.gen,
+			owner: self.owner,
+			group: self.group,
+			label: self.label,
 		}
 	}
 }
@@ -289,6 +2196,303 @@ impl<T: Debug> Debug for Node<T> {
 			.field("elem", &self.elem)
 			.field("pre", &self.pre)
 			.field("next", &self.next)
+			.field("gen", &self.gen)
+			.field("owner", &self.owner)
+			.field("group", &self.group)
+			.field("label", &self.label)
 			.finish()
 	}
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+use pi_slab::Slab;
+
+#[test]
+fn test_cursor(){
+	let mut slab: Slab<Node<u32>> = Slab::new();
+	let mut deque: Deque<u32, Slab<Node<u32>>> = Deque::new();
+	for i in 0..5 {
+		deque.push_back(i, &mut slab);
+	}
+
+	let mut cursor = deque.cursor_back(&slab);
+	assert_eq!(cursor.index(), Some(4));
+	cursor.move_next();
+	assert_eq!(cursor.current(), None);
+	assert_eq!(cursor.peek_next(), Some(&0));
+	cursor.move_prev();
+	cursor.move_prev();
+	assert_eq!(cursor.current(), Some(&3));
+
+	let mut cursor = deque.cursor_front_mut(&mut slab);
+	cursor.move_next();
+	cursor.insert_before(10);
+	cursor.insert_after(11);
+	assert_eq!(cursor.index(), Some(2));
+	assert_eq!(cursor.remove_current(), Some(1));
+	assert_eq!(cursor.current(), Some(&mut 11));
+	*cursor.current().unwrap() += 1;
+
+	let mut tail = cursor.split_after();
+	let head = cursor.split_before();
+	assert_eq!(head.iter(&slab).copied().collect::<Vec<u32>>(), vec![0, 10]);
+	assert_eq!(deque.iter(&slab).copied().collect::<Vec<u32>>(), vec![12]);
+	assert_eq!(tail.iter(&slab).rev().copied().collect::<Vec<u32>>(), vec![4, 3, 2]);
+	assert_eq!((head.len(), deque.len(), tail.len()), (2, 1, 3));
+
+	let mut cursor = tail.cursor_front_mut(&mut slab);
+	cursor.move_prev();
+	cursor.insert_after(5);
+	let all = cursor.split_after();
+	assert_eq!(all.iter(&slab).copied().collect::<Vec<u32>>(), vec![5, 2, 3, 4]);
+	assert!(tail.is_empty());
+}
+
+#[test]
+fn test_insert_wrong_owner(){
+	let mut slab: Slab<Node<u32>> = Slab::new();
+	let mut deque1: Deque<u32, Slab<Node<u32>>> = Deque::new();
+	let mut deque2: Deque<u32, Slab<Node<u32>>> = Deque::new();
+	let i1 = deque1.push_back(1, &mut slab);
+	deque1.push_back(2, &mut slab);
+	let i3 = deque2.push_back(3, &mut slab);
+
+	assert_eq!(deque2.insert_after(i1, 4, &mut slab), Err(DequeError::WrongOwner(i1)));
+	assert_eq!(deque1.insert_before(i3, 4, &mut slab), Err(DequeError::WrongOwner(i3)));
+	assert_eq!((deque1.len(), deque2.len()), (2, 1));
+
+	// 队列中间的节点同样可以被识别
+	let i4 = deque2.push_front(4, &mut slab);
+	deque2.push_front(5, &mut slab);
+	assert_eq!(deque1.move_to_back(i4, &mut slab), Err(DequeError::WrongOwner(i4)));
+	assert_eq!(deque1.try_remove(i4, &mut slab), None);
+	assert_eq!(deque1.get(i4, &slab), None);
+	assert_eq!(deque2.get(i4, &slab), Some(&4));
+}
+
+#[test]
+fn test_sort_panic(){
+	use std::panic::{catch_unwind, AssertUnwindSafe};
+
+	let mut slab: Slab<Node<String>> = Slab::new();
+	let mut deque: Deque<String, Slab<Node<String>>> = Deque::new();
+	for i in [5, 3, 8, 1, 9, 2, 7] {
+		deque.push_back(i.to_string(), &mut slab);
+	}
+	let mut calls = 0;
+	let r = catch_unwind(AssertUnwindSafe(|| {
+		deque.sort_by(|a, b| {
+			calls += 1;
+			if calls > 5 {
+				panic!("compare failed");
+			}
+			a.cmp(b)
+		}, &mut slab)
+	}));
+	assert!(r.is_err());
+	// 比较函数panic后，队列保持原样，可以正常遍历和清空
+	deque.validate(&slab).unwrap();
+	assert_eq!(deque.iter(&slab).cloned().collect::<Vec<String>>(), vec!["5", "3", "8", "1", "9", "2", "7"]);
+	deque.clear(&mut slab);
+	assert_eq!(slab.len(), 0);
+}
+
+#[test]
+fn test_transfer(){
+	let mut slab: Slab<Node<u32>> = Slab::new();
+	let mut deque1: Deque<u32, Slab<Node<u32>>> = Deque::new();
+	let mut deque2: Deque<u32, Slab<Node<u32>>> = Deque::new();
+	let i1 = deque1.push_back(1, &mut slab);
+	let i2 = deque1.push_back(2, &mut slab);
+	deque2.push_back(3, &mut slab);
+	let handle = deque1.handle(i2, &mut slab).unwrap();
+
+	deque1.transfer_to_back(i2, &mut deque2, &mut slab).unwrap();
+	deque1.transfer_to_front(i1, &mut deque2, &mut slab).unwrap();
+	assert_eq!(deque1.transfer_to_back(i1, &mut deque2, &mut slab), Err(DequeError::WrongOwner(i1)));
+	assert!(deque1.is_empty());
+	assert_eq!(deque2.iter(&slab).copied().collect::<Vec<u32>>(), vec![1, 3, 2]);
+	// 转移后索引和句柄仍然有效
+	assert_eq!(deque2.get_by_handle(handle, &slab), Some(&2));
+	assert!(deque1.validate(&slab).is_ok() && deque2.validate(&slab).is_ok());
+}
+
+#[test]
+fn test_append_split(){
+	let mut slab: Slab<Node<u32>> = Slab::new();
+	let mut deque1: Deque<u32, Slab<Node<u32>>> = Deque::new();
+	let mut deque2: Deque<u32, Slab<Node<u32>>> = Deque::new();
+	let i0 = deque1.push_back(0, &mut slab);
+	deque1.push_back(1, &mut slab);
+	let i2 = deque2.push_back(2, &mut slab);
+	deque2.push_back(3, &mut slab);
+
+	let id1 = deque1.id();
+	deque1.append(&mut deque2, &mut slab);
+	assert!(deque2.is_empty());
+	// 分离不会改变原队列的标识
+	assert_ne!(deque1.id(), deque2.id());
+	assert_eq!(deque1.iter(&slab).copied().collect::<Vec<u32>>(), vec![0, 1, 2, 3]);
+	assert_eq!(deque1.iter(&slab).rev().copied().collect::<Vec<u32>>(), vec![3, 2, 1, 0]);
+
+	let tail = deque1.split_off(i2, &mut slab).unwrap();
+	assert_eq!(deque1.id(), id1);
+	assert_eq!(tail.iter(&slab).copied().collect::<Vec<u32>>(), vec![2, 3]);
+	assert_eq!(deque1.iter(&slab).copied().collect::<Vec<u32>>(), vec![0, 1]);
+	assert_eq!((deque1.len(), tail.len()), (2, 2));
+
+	let mut all = deque1.split_off(i0, &mut slab).unwrap();
+	assert!(deque1.is_empty());
+	deque1.append(&mut all, &mut slab);
+	assert_eq!(deque1.iter(&slab).copied().collect::<Vec<u32>>(), vec![0, 1]);
+	assert!(deque1.contains(i0, &slab));
+	assert!(!tail.contains(i0, &slab) && !all.contains(i0, &slab));
+	assert!(tail.contains(i2, &slab));
+}
+
+#[test]
+fn test_validate(){
+	let mut slab: Slab<Node<u32>> = Slab::new();
+	let mut deque: Deque<u32, Slab<Node<u32>>> = Deque::new();
+	assert_eq!(deque.validate(&slab), Ok(()));
+	let i0 = deque.push_back(0, &mut slab);
+	let i1 = deque.push_back(1, &mut slab);
+	let i2 = deque.push_back(2, &mut slab);
+	assert_eq!(deque.validate(&slab), Ok(()));
+
+	slab[i2].pre = i0;
+	assert_eq!(deque.validate(&slab), Err(CorruptionError { index: i2, kind: CorruptionKind::BrokenPre(i0) }));
+	slab[i2].pre = i1;
+
+	slab[i1].next = 0;
+	assert_eq!(deque.validate(&slab), Err(CorruptionError { index: i1, kind: CorruptionKind::EndsBeforeLast }));
+	slab[i1].next = i2;
+
+	slab[i0].pre = i2;
+	assert_eq!(deque.validate(&slab), Err(CorruptionError { index: i0, kind: CorruptionKind::FirstHasPre(i2) }));
+	slab[i0].pre = 0;
+
+	let mut other = deque.split_off(i2, &mut slab).unwrap();
+	deque.len = 3;
+	assert_eq!(deque.validate(&slab), Err(CorruptionError { index: 0, kind: CorruptionKind::LenMismatch { len: 3, count: 2 } }));
+	deque.len = 2;
+	deque.append(&mut other, &mut slab);
+	assert_eq!(deque.validate(&slab), Ok(()));
+	assert_eq!(other.validate(&slab), Ok(()));
+}
+
+#[test]
+fn test_clone_into(){
+	let mut src: Slab<Node<u32>> = Slab::new();
+	let mut dst: Slab<Node<u32>> = Slab::new();
+	let mut other: Deque<u32, Slab<Node<u32>>> = Deque::new();
+	let mut deque: Deque<u32, Slab<Node<u32>>> = Deque::new();
+	other.push_back(10, &mut dst);
+	let i0 = deque.push_back(0, &mut src);
+	let i1 = deque.push_front(1, &mut src);
+
+	let (copy, indexs) = deque.clone_into(&src, &mut dst);
+	assert_eq!(copy.iter(&dst).copied().collect::<Vec<u32>>(), vec![1, 0]);
+	assert_eq!(copy.get(indexs[&i0], &dst), Some(&0));
+	assert_eq!(copy.get(indexs[&i1], &dst), Some(&1));
+	assert_eq!(copy.validate(&dst), Ok(()));
+	assert_eq!(other.validate(&dst), Ok(()));
+	assert_eq!(deque.validate(&src), Ok(()));
+}
+
+#[test]
+fn test_precedes(){
+	let mut slab: Slab<Node<u32>> = Slab::new();
+	let mut deque: Deque<u32, Slab<Node<u32>>> = Deque::new();
+	let mut other: Deque<u32, Slab<Node<u32>>> = Deque::new();
+	let first = deque.push_back(0, &mut slab);
+	let last = deque.push_back(1, &mut slab);
+	// 第一次比较时分配标签，此后随插入维护
+	assert_eq!(deque.precedes(first, last, &mut slab), Ok(true));
+	// 反复在同一位置插入，耗尽标签间隔，触发重新分配标签
+	let mut anchor = first;
+	let mut indexs = vec![first];
+	for i in 0..200 {
+		anchor = deque.insert_after(anchor, i, &mut slab).unwrap();
+		indexs.push(anchor);
+		let front = deque.push_front(i, &mut slab);
+		indexs.insert(0, front);
+	}
+	indexs.push(last);
+	assert_eq!(deque.validate(&slab), Ok(()));
+	for w in indexs.windows(2).step_by(7) {
+		assert_eq!(deque.precedes(w[0], w[1], &mut slab), Ok(true));
+		assert_eq!(deque.precedes(w[1], w[0], &mut slab), Ok(false));
+	}
+
+	deque.move_to_front(last, &mut slab).unwrap();
+	assert_eq!(deque.precedes(last, first, &mut slab), Ok(true));
+	let o = other.push_back(5, &mut slab);
+	assert_eq!(deque.precedes(first, o, &mut slab), Err(DequeError::WrongOwner(o)));
+
+	// 拼接后两侧的标签仍然有序
+	for i in 0..3 {
+		other.push_front(i, &mut slab);
+	}
+	other.append(&mut deque, &mut slab);
+	assert_eq!(other.validate(&slab), Ok(()));
+	assert_eq!(other.precedes(o, first, &mut slab), Ok(true));
+	other.sort(&mut slab);
+	assert_eq!(other.validate(&slab), Ok(()));
+	let (front, back) = (other.get_first(), other.get_last());
+	assert_eq!(other.precedes(front, back, &mut slab), Ok(true));
+
+	// 在交替的位置大量插入，重新分配标签后仍然有序
+	let mut anchors = vec![other.get_first(), other.get_last()];
+	for i in 0..5000 {
+		let anchor = anchors[i % anchors.len()];
+		let index = match i % 3 {
+			0 => other.insert_before(anchor, i as u32, &mut slab).unwrap(),
+			_ => other.insert_after(anchor, i as u32, &mut slab).unwrap(),
+		};
+		if i % 5 == 0 {
+			anchors.push(index);
+		}
+	}
+	assert_eq!(other.validate(&slab), Ok(()));
+}
+
+#[test]
+fn test_precedes_groups(){
+	let mut slab: Slab<Node<u32>> = Slab::new();
+	let mut deque: Deque<u32, Slab<Node<u32>>> = Deque::new();
+	let indexs: Vec<usize> = (0..300).map(|i| deque.push_back(i, &mut slab)).collect();
+	assert_eq!(deque.precedes(indexs[0], indexs[299], &mut slab), Ok(true));
+	// 旋转、拆分、拼接跨越多个分组，分组仍然连续且标签有序
+	deque.rotate_left(100, &mut slab);
+	assert_eq!(deque.validate(&slab), Ok(()));
+	assert_eq!(deque.precedes(indexs[299], indexs[0], &mut slab), Ok(true));
+	assert_eq!(deque.precedes(indexs[100], indexs[99], &mut slab), Ok(true));
+
+	let mut tail = deque.split_off(indexs[150], &mut slab).unwrap();
+	assert_eq!(deque.validate(&slab), Ok(()));
+	assert_eq!(tail.validate(&slab), Ok(()));
+	assert_eq!(tail.precedes(indexs[150], indexs[0], &mut slab), Ok(true));
+
+	for &i in indexs[120..200].iter() {
+		if slab[i].owner == deque.id() {
+			deque.remove(i, &mut slab);
+		} else {
+			tail.remove(i, &mut slab);
+		}
+	}
+	assert_eq!(deque.validate(&slab), Ok(()));
+	assert_eq!(tail.validate(&slab), Ok(()));
+
+	tail.append(&mut deque, &mut slab);
+	assert_eq!(tail.validate(&slab), Ok(()));
+	assert_eq!(deque.validate(&slab), Ok(()));
+	let mut order = vec![tail.get_first()];
+	while slab[*order.last().unwrap()].next != 0 {
+		order.push(slab[*order.last().unwrap()].next);
+	}
+	assert_eq!(order.len(), tail.len());
+	for w in order.windows(2) {
+		assert_eq!(tail.precedes(w[0], w[1], &mut slab), Ok(true));
+	}
+}
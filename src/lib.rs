@@ -10,8 +10,8 @@
 //! 选择:
 //! - 当你需要使用双端队列，并且你不需要快速从任意位置删除和查询，标准库中的双端队列是一个不错的选择
 //! - 当你的部分功能需要使用从任意位置删除和查询，部分功能不需要时，不太建议你同时依赖标准库与本库的双端队列，毕竟会增减应用程序的尺寸
-//! 但如果你不在意，你可以这么做！这种情况下，
-//! 建议的做法是，总是使用本库或其它的代替品,本库的双端队列性能仅比标准库略低（删除功能也需要一定成本）
+//!   但如果你不在意，你可以这么做！这种情况下，
+//!   建议的做法是，总是使用本库或其它的代替品,本库的双端队列性能仅比标准库略低（删除功能也需要一定成本）
 
 extern crate pi_slab;
 
@@ -22,6 +22,30 @@ pub mod deque;
 /// 当不需要与其它数据结构结合时，你可以使用slab_deque为你提供的双端队列默认实现
 pub mod slab_deque;
 
+/// 在slab_deque的基础上维护顺序统计信息，支持O(log n)按位置取元素和取元素的位置
+pub mod ranked_deque;
+
+/// 基于slab_deque的轮询调度队列，轮询时元素索引保持不变
+pub mod round_robin;
+
+/// 基于deque和HashMap的LRU缓存
+pub mod lru;
+
+/// 基于deque和HashMap的映射表，按插入顺序迭代
+pub mod linked_map;
+
+/// 基于deque和HashMap的去重队列，同一个键在队列中最多出现一次
+pub mod unique_deque;
+
+/// 共享同一个slab的多个双端队列，元素可以在队列间移动而索引不变
+pub mod multi_deque;
+
+/// 基于multi_deque的多级优先队列，修改优先级时元素索引不变
+pub mod priority_deque;
+
+/// 槽为共享slab的双端队列的分层时间轮，支持O(1)取消定时器
+pub mod timer_wheel;
+
 
 // use std::fmt::{Debug, Formatter, Result as FResult};
 
@@ -0,0 +1,334 @@
+//! 按插入顺序迭代的映射表
+//! 用deque维护键值对的插入顺序，用HashMap记录键到队列索引的映射
+//! 查询、插入、删除以及将键值对移动到尾部都是O(1)的，迭代时按插入顺序返回键值对
+//! 例如：协议消息的字段、配置的合并，需要确定的、与插入顺序一致的迭代顺序
+
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry as MapEntry;
+use std::fmt::{Debug, Formatter, Result as FResult};
+use std::hash::Hash;
+
+use pi_slab::Slab;
+use crate::deque::{ Deque, Node, Iter as DIter };
+
+// 键值对的节点存储
+type EntrySlab<K, V> = Slab<Node<(K, V)>>;
+
+/// 按插入顺序迭代的映射表
+pub struct LinkedMap<K, V>{
+    deque: Deque<(K, V), EntrySlab<K, V>>,
+    slab: EntrySlab<K, V>,
+    map: HashMap<K, usize>,
+}
+
+impl<K: Hash + Eq + Clone, V> Default for LinkedMap<K, V> {
+    fn default() -> LinkedMap<K, V> {
+        LinkedMap::new()
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> LinkedMap<K, V> {
+    pub fn new() -> LinkedMap<K, V> {
+        Self {
+            deque: Deque::new(),
+            slab: Slab::new(),
+            map: HashMap::new(),
+        }
+    }
+
+    /// Inserts a key-value pair at the back, if the key exists, the value is replaced in place and the old value is returned.
+    /// 在尾部插入一个键值对；如果键已存在，则原地替换值（不改变其顺序），并返回旧值
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.insert_full(key, value).1
+    }
+
+    /// Same as insert, but also returns the index of the key-value pair.
+    /// 与insert相同，同时返回键值对在队列中的索引
+    pub fn insert_full(&mut self, key: K, value: V) -> (usize, Option<V>) {
+        match self.map.entry(key) {
+            MapEntry::Occupied(e) => {
+                let index = *e.get();
+                let (_, v) = self.deque.get_mut(index, &mut self.slab).unwrap();
+                (index, Some(std::mem::replace(v, value)))
+            },
+            MapEntry::Vacant(e) => {
+                let index = self.deque.push_back((e.key().clone(), value), &mut self.slab);
+                e.insert(index);
+                (index, None)
+            },
+        }
+    }
+
+    /// Removes a key from the map, returning its value.
+    /// 删除键，并返回其对应的值
+    pub fn remove<Q: Hash + Eq + ?Sized>(&mut self, key: &Q) -> Option<V> where K: Borrow<Q> {
+        let index = self.map.remove(key)?;
+        Some(self.deque.remove(index, &mut self.slab).1)
+    }
+
+    /// Returns a reference to the value of the key.
+    /// 取到键对应的值
+    pub fn get<Q: Hash + Eq + ?Sized>(&self, key: &Q) -> Option<&V> where K: Borrow<Q> {
+        let index = *self.map.get(key)?;
+        self.deque.get(index, &self.slab).map(|(_, v)| v)
+    }
+
+    /// Returns a mutable reference to the value of the key.
+    /// 取到键对应的值的可变引用
+    pub fn get_mut<Q: Hash + Eq + ?Sized>(&mut self, key: &Q) -> Option<&mut V> where K: Borrow<Q> {
+        let index = *self.map.get(key)?;
+        self.deque.get_mut(index, &mut self.slab).map(|(_, v)| v)
+    }
+
+    /// Returns the index of the key-value pair in the underlying deque, the index is unchanged until the key is removed.
+    /// 取到键值对在队列中的索引，在键被删除之前，索引保持不变
+    pub fn index_of<Q: Hash + Eq + ?Sized>(&self, key: &Q) -> Option<usize> where K: Borrow<Q> {
+        self.map.get(key).copied()
+    }
+
+    /// 映射表中是否存在键
+    pub fn contains_key<Q: Hash + Eq + ?Sized>(&self, key: &Q) -> bool where K: Borrow<Q> {
+        self.map.contains_key(key)
+    }
+
+    /// Returns the first key-value pair in insertion order.
+    /// 取到最早插入的键值对
+    pub fn front(&self) -> Option<(&K, &V)> {
+        self.deque.front(&self.slab).map(|(k, v)| (k, v))
+    }
+
+    /// Returns the last key-value pair in insertion order.
+    /// 取到最晚插入的键值对
+    pub fn back(&self) -> Option<(&K, &V)> {
+        self.deque.back(&self.slab).map(|(k, v)| (k, v))
+    }
+
+    /// Removes and returns the first key-value pair in insertion order.
+    /// 弹出最早插入的键值对
+    pub fn pop_front(&mut self) -> Option<(K, V)> {
+        let (key, value) = self.deque.pop_front(&mut self.slab)?;
+        self.map.remove(&key);
+        Some((key, value))
+    }
+
+    /// Removes and returns the last key-value pair in insertion order.
+    /// 弹出最晚插入的键值对
+    pub fn pop_back(&mut self) -> Option<(K, V)> {
+        let (key, value) = self.deque.pop_back(&mut self.slab)?;
+        self.map.remove(&key);
+        Some((key, value))
+    }
+
+    /// Moves the key-value pair to the back, as if it was inserted last. return false if the key not exists.
+    /// 将键值对移动到尾部，如同它是最晚插入的；如果键不存在，则返回false
+    pub fn move_to_back<Q: Hash + Eq + ?Sized>(&mut self, key: &Q) -> bool where K: Borrow<Q> {
+        match self.map.get(key) {
+            // 索引来自map，一定是本队列的元素
+            Some(index) => self.deque.move_to_back(*index, &mut self.slab).is_ok(),
+            None => false,
+        }
+    }
+
+    /// Gets the entry of the key for in-place manipulation.
+    /// 取到键对应的条目，用于原地查询或插入
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V> {
+        match self.map.get(&key) {
+            Some(index) => Entry::Occupied(OccupiedEntry {
+                index: *index,
+                map: self,
+            }),
+            None => Entry::Vacant(VacantEntry {
+                key,
+                map: self,
+            }),
+        }
+    }
+
+    /// 映射表中键值对的个数
+    pub fn len(&self) -> usize {
+        self.deque.len()
+    }
+
+    /// 映射表是否为空
+    pub fn is_empty(&self) -> bool {
+        self.deque.is_empty()
+    }
+
+    /// 清空映射表
+    pub fn clear(&mut self) {
+        self.deque.clear(&mut self.slab);
+        self.map.clear();
+    }
+
+    /// 创建映射表的迭代器，按插入顺序迭代
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter{
+            d_iter: self.deque.iter(&self.slab),
+        }
+    }
+}
+
+impl<K: Debug, V: Debug> Debug for LinkedMap<K, V> {
+    fn fmt(&self, f: &mut Formatter) -> FResult {
+        f.debug_map()
+            .entries(self.deque.iter(&self.slab).map(|(k, v)| (k, v)))
+            .finish()
+    }
+}
+
+/// 映射表中的条目，可能已存在，也可能不存在
+pub enum Entry<'a, K, V> {
+    Occupied(OccupiedEntry<'a, K, V>),
+    Vacant(VacantEntry<'a, K, V>),
+}
+
+impl<'a, K: Hash + Eq + Clone, V> Entry<'a, K, V> {
+    /// 如果条目不存在，则在尾部插入default，返回值的可变引用
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        match self {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => e.insert(default),
+        }
+    }
+
+    /// 如果条目不存在，则在尾部插入default()的返回值，返回值的可变引用
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => e.insert(default()),
+        }
+    }
+
+    /// 如果条目已存在，则用f修改其值
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(e) = &mut self {
+            f(e.get_mut());
+        }
+        self
+    }
+
+    /// 条目的键
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(e) => e.key(),
+            Entry::Vacant(e) => &e.key,
+        }
+    }
+}
+
+/// 已存在的条目
+pub struct OccupiedEntry<'a, K, V> {
+    map: &'a mut LinkedMap<K, V>,
+    index: usize,
+}
+
+impl<'a, K: Hash + Eq + Clone, V> OccupiedEntry<'a, K, V> {
+    /// 条目的键
+    pub fn key(&self) -> &K {
+        &self.map.deque.get(self.index, &self.map.slab).unwrap().0
+    }
+
+    /// 条目的值
+    pub fn get(&self) -> &V {
+        &self.map.deque.get(self.index, &self.map.slab).unwrap().1
+    }
+
+    /// 条目的值的可变引用
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.map.deque.get_mut(self.index, &mut self.map.slab).unwrap().1
+    }
+
+    /// 将条目转换为生命周期与映射表相同的值的可变引用
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self.map.deque.get_mut(self.index, &mut self.map.slab).unwrap().1
+    }
+
+    /// 替换条目的值，并返回旧值
+    pub fn insert(&mut self, value: V) -> V {
+        std::mem::replace(self.get_mut(), value)
+    }
+
+    /// 删除条目，并返回其键值对
+    pub fn remove(self) -> (K, V) {
+        let (key, value) = self.map.deque.remove(self.index, &mut self.map.slab);
+        self.map.map.remove(&key);
+        (key, value)
+    }
+}
+
+/// 不存在的条目
+pub struct VacantEntry<'a, K, V> {
+    map: &'a mut LinkedMap<K, V>,
+    key: K,
+}
+
+impl<'a, K: Hash + Eq + Clone, V> VacantEntry<'a, K, V> {
+    /// 条目的键
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// 在尾部插入值，并返回值的可变引用
+    pub fn insert(self, value: V) -> &'a mut V {
+        let index = self.map.deque.push_back((self.key.clone(), value), &mut self.map.slab);
+        self.map.map.insert(self.key, index);
+        &mut self.map.deque.get_mut(index, &mut self.map.slab).unwrap().1
+    }
+}
+
+pub struct Iter<'a, K: 'a, V: 'a> {
+    d_iter: DIter<'a, (K, V), EntrySlab<K, V>>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<(&'a K, &'a V)> {
+        self.d_iter.next().map(|(k, v)| (k, v))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.d_iter.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Iter<'a, K, V> {
+    fn next_back(&mut self) -> Option<(&'a K, &'a V)> {
+        self.d_iter.next_back().map(|(k, v)| (k, v))
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> {}
+
+#[test]
+fn test_linked_map(){
+    let mut map: LinkedMap<&str, u32> = LinkedMap::new();
+    assert_eq!(map.insert("b", 1), None);
+    assert_eq!(map.insert("a", 2), None);
+    assert_eq!(map.insert("c", 3), None);
+    // 替换值不改变顺序
+    assert_eq!(map.insert("b", 10), Some(1));
+    assert_eq!(map.insert_full("a", 2), (map.index_of("a").unwrap(), Some(2)));
+    assert_eq!(map.iter().collect::<Vec<(&&str, &u32)>>(), vec![(&"b", &10), (&"a", &2), (&"c", &3)]);
+
+    assert!(map.move_to_back("b"));
+    assert!(!map.move_to_back("d"));
+    assert_eq!(map.iter().map(|(k, _)| *k).collect::<Vec<&str>>(), vec!["a", "c", "b"]);
+    assert_eq!(map.remove("c"), Some(3));
+    assert_eq!(map.get("c"), None);
+    *map.get_mut("a").unwrap() += 1;
+
+    *map.entry("a").or_insert(0) += 1;
+    map.entry("d").and_modify(|v| *v += 1).or_insert_with(|| 4);
+    map.entry("b").and_modify(|v| *v += 1).or_insert(0);
+    assert_eq!(map.iter().rev().map(|(k, v)| (*k, *v)).collect::<Vec<(&str, u32)>>(), vec![("d", 4), ("b", 11), ("a", 4)]);
+    if let Entry::Occupied(e) = map.entry("b") {
+        assert_eq!(e.remove(), ("b", 11));
+    }
+
+    assert_eq!(map.pop_front(), Some(("a", 4)));
+    assert_eq!(map.pop_back(), Some(("d", 4)));
+    assert_eq!(map.pop_back(), None);
+    assert!(map.is_empty() && !map.contains_key("a"));
+}
@@ -0,0 +1,180 @@
+//! LRU缓存
+//! 用linked_map维护元素的访问顺序（头部为最久未使用，尾部为最近使用）
+//! 访问元素时，通过move_to_back将其移动到尾部，插入新元素超出容量时，通过pop_front从头部淘汰元素，都是O(1)的
+//! 移动节点不会改变元素索引，因此linked_map中键到索引的映射始终有效
+
+use std::borrow::Borrow;
+use std::fmt::{Debug, Formatter, Result as FResult};
+use std::hash::Hash;
+use std::iter::Rev;
+
+use crate::linked_map::{ LinkedMap, Iter };
+
+/// LRU缓存
+pub struct LruCache<K, V>{
+    map: LinkedMap<K, V>,
+    capacity: usize,
+    evict: Option<Box<dyn FnMut(K, V)>>,
+}
+
+impl<K: Hash + Eq + Clone, V> LruCache<K, V> {
+    /// 创建一个容量为capacity的缓存
+    pub fn new(capacity: usize) -> LruCache<K, V> {
+        Self {
+            map: LinkedMap::new(),
+            capacity,
+            evict: None,
+        }
+    }
+
+    /// 创建一个容量为capacity的缓存，元素因超出容量被淘汰时，将调用evict
+    pub fn with_evict<F: FnMut(K, V) + 'static>(capacity: usize, evict: F) -> LruCache<K, V> {
+        let mut cache = LruCache::new(capacity);
+        cache.evict = Some(Box::new(evict));
+        cache
+    }
+
+    /// Returns a reference to the value of the key, and marks it as the most recently used.
+    /// 取到键对应的值，并将其标记为最近使用
+    pub fn get<Q: Hash + Eq + ?Sized>(&mut self, key: &Q) -> Option<&V> where K: Borrow<Q> {
+        if !self.map.move_to_back(key) {
+            return None;
+        }
+        self.map.get(key)
+    }
+
+    /// Returns a mutable reference to the value of the key, and marks it as the most recently used.
+    /// 取到键对应的值的可变引用，并将其标记为最近使用
+    pub fn get_mut<Q: Hash + Eq + ?Sized>(&mut self, key: &Q) -> Option<&mut V> where K: Borrow<Q> {
+        if !self.map.move_to_back(key) {
+            return None;
+        }
+        self.map.get_mut(key)
+    }
+
+    /// Returns a reference to the value of the key, without changing its usage.
+    /// 取到键对应的值，不改变其使用顺序
+    pub fn peek<Q: Hash + Eq + ?Sized>(&self, key: &Q) -> Option<&V> where K: Borrow<Q> {
+        self.map.get(key)
+    }
+
+    /// 缓存中是否存在键
+    pub fn contains<Q: Hash + Eq + ?Sized>(&self, key: &Q) -> bool where K: Borrow<Q> {
+        self.map.contains_key(key)
+    }
+
+    /// Puts a value into the cache as the most recently used, return the old value of the key.
+    /// If the cache is full, the least recently used element is evicted.
+    /// 放入一个值，并将其标记为最近使用，如果键已存在，则返回旧值；超出容量时，淘汰最久未使用的元素
+    pub fn put(&mut self, key: K, value: V) -> Option<V> {
+        if self.map.move_to_back(&key) {
+            return self.map.insert(key, value);
+        }
+        self.map.insert(key, value);
+        self.shrink();
+        None
+    }
+
+    /// Removes the value of the key from the cache.
+    /// 删除键对应的值，并返回该值
+    pub fn remove<Q: Hash + Eq + ?Sized>(&mut self, key: &Q) -> Option<V> where K: Borrow<Q> {
+        self.map.remove(key)
+    }
+
+    /// Removes and returns the least recently used element, the eviction callback is not called.
+    /// 弹出最久未使用的元素，不会调用淘汰回调
+    pub fn pop_lru(&mut self) -> Option<(K, V)> {
+        self.map.pop_front()
+    }
+
+    /// Returns the least recently used element, without changing its usage.
+    /// 取到最久未使用的元素，不改变其使用顺序
+    pub fn peek_lru(&self) -> Option<(&K, &V)> {
+        self.map.front()
+    }
+
+    /// Resizes the cache, the least recently used elements are evicted if the cache is shrunk.
+    /// 修改缓存容量，如果元素个数超出新容量，则淘汰最久未使用的元素
+    pub fn resize(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.shrink();
+    }
+
+    /// 缓存的容量
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// 缓存中元素的个数
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// 缓存是否为空
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// 清空缓存，不会调用淘汰回调
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    /// 创建缓存的迭代器，从最近使用到最久未使用
+    pub fn iter(&self) -> Rev<Iter<'_, K, V>> {
+        self.map.iter().rev()
+    }
+
+    // 淘汰超出容量的元素
+    fn shrink(&mut self) {
+        while self.map.len() > self.capacity {
+            let (key, value) = match self.pop_lru() {
+                Some(elem) => elem,
+                None => return,
+            };
+            if let Some(evict) = &mut self.evict {
+                evict(key, value);
+            }
+        }
+    }
+}
+
+impl<K: Debug, V: Debug> Debug for LruCache<K, V> {
+    fn fmt(&self, f: &mut Formatter) -> FResult {
+        f.debug_struct("LruCache")
+            .field("map", &self.map)
+            .field("capacity", &self.capacity)
+            .finish()
+    }
+}
+
+#[test]
+fn test_lru(){
+    use std::rc::Rc;
+    use std::cell::RefCell;
+
+    let evicted = Rc::new(RefCell::new(Vec::new()));
+    let e = evicted.clone();
+    let mut cache: LruCache<String, u32> = LruCache::with_evict(3, move |k, v| e.borrow_mut().push((k, v)));
+    cache.put("a".to_string(), 1);
+    cache.put("b".to_string(), 2);
+    cache.put("c".to_string(), 3);
+    assert_eq!(cache.get("a"), Some(&1));
+    assert_eq!(cache.put("b".to_string(), 20), Some(2));
+
+    // 最久未使用的是c
+    assert_eq!(cache.peek_lru(), Some((&"c".to_string(), &3)));
+    cache.put("d".to_string(), 4);
+    assert_eq!(*RefCell::borrow(&evicted), vec![("c".to_string(), 3)]);
+    assert_eq!(cache.iter().map(|(k, v)| (k.as_str(), *v)).collect::<Vec<(&str, u32)>>(), vec![("d", 4), ("b", 20), ("a", 1)]);
+
+    assert_eq!(cache.peek("a"), Some(&1));
+    assert_eq!(cache.pop_lru(), Some(("a".to_string(), 1)));
+    *cache.get_mut("b").unwrap() += 1;
+    cache.put("e".to_string(), 5);
+    cache.resize(1);
+    assert_eq!(*RefCell::borrow(&evicted), vec![("c".to_string(), 3), ("d".to_string(), 4), ("b".to_string(), 21)]);
+    assert_eq!(cache.len(), 1);
+    assert_eq!(cache.remove("e"), Some(5));
+    assert!(cache.is_empty() && !cache.contains("e"));
+}
@@ -0,0 +1,238 @@
+//! 共享同一个slab的多个双端队列
+//! 所有队列的节点都存放在同一个Slab中，因此元素索引在所有队列间唯一，可以直接用索引删除元素，而不需要知道元素所在的队列
+//! 节点的owner记录了其所属队列的标识，通过它可以在O(1)时间内找到元素所在的队列
+//! 将元素从一个队列移动到另一个队列只是重新链接节点，元素索引保持不变
+
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter, Result as FResult};
+
+use pi_slab::Slab;
+use crate::deque::{ Deque, Node, DequeError, Handle, Iter };
+
+/// 队列的标识，由MultiDeque::add_queue分配
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct QueueId(usize);
+
+impl QueueId {
+    /// 队列在MultiDeque中的序号，从0开始
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+/// 共享同一个slab的多个双端队列
+pub struct MultiDeque<T>{
+    queues: Vec<Deque<T, Slab<Node<T>>>>,
+    slab: Slab<Node<T>>,
+    // 队列的id（即节点的owner）到队列标识的映射
+    owners: HashMap<u32, QueueId>,
+}
+
+impl<T> Default for MultiDeque<T> {
+    fn default() -> MultiDeque<T> {
+        MultiDeque::new()
+    }
+}
+
+impl<T> MultiDeque<T> {
+    pub fn new() -> MultiDeque<T> {
+        Self {
+            queues: Vec::new(),
+            slab: Slab::new(),
+            owners: HashMap::new(),
+        }
+    }
+
+    /// 创建一个新的空队列，返回其标识
+    pub fn add_queue(&mut self) -> QueueId {
+        let qid = QueueId(self.queues.len());
+        let deque = Deque::new();
+        self.owners.insert(deque.id(), qid);
+        self.queues.push(deque);
+        qid
+    }
+
+    /// 队列的个数
+    pub fn queue_count(&self) -> usize {
+        self.queues.len()
+    }
+
+    /// Append an element to the queue. return a index
+    /// 在队列尾部压入一个元素，返回元素索引，如果qid不是本结构分配的标识，将会panic
+    pub fn push_back(&mut self, qid: QueueId, elem: T) -> usize {
+        self.queues[qid.0].push_back(elem, &mut self.slab)
+    }
+
+    /// Prepend an element to the queue. return a index
+    /// 在队列头部压入一个元素，返回元素索引，如果qid不是本结构分配的标识，将会panic
+    pub fn push_front(&mut self, qid: QueueId, elem: T) -> usize {
+        self.queues[qid.0].push_front(elem, &mut self.slab)
+    }
+
+    /// Append an element to the queue. return a handle
+    /// 在队列尾部压入一个元素，返回元素句柄，如果qid不是本结构分配的标识，将会panic
+    pub fn push_back_handle(&mut self, qid: QueueId, elem: T) -> Handle {
+        self.queues[qid.0].push_back_handle(elem, &mut self.slab)
+    }
+
+    /// Removes the first element from the queue and returns it, or None if it is empty.
+    /// 从队列头部弹出一个元素，如果队列中没有元素，则返回None
+    pub fn pop_front(&mut self, qid: QueueId) -> Option<T> {
+        self.queues[qid.0].pop_front(&mut self.slab)
+    }
+
+    /// Removes the last element from the queue and returns it, or None if it is empty.
+    /// 从队列尾部弹出一个元素，如果队列中没有元素，则返回None
+    pub fn pop_back(&mut self, qid: QueueId) -> Option<T> {
+        self.queues[qid.0].pop_back(&mut self.slab)
+    }
+
+    /// 取到队列头部元素的索引，如果队列为空，则返回0
+    pub fn get_first(&self, qid: QueueId) -> usize {
+        self.queues[qid.0].get_first()
+    }
+
+    /// 取到队列尾部元素的索引，如果队列为空，则返回0
+    pub fn get_last(&self, qid: QueueId) -> usize {
+        self.queues[qid.0].get_last()
+    }
+
+    /// Returns the queue which the element at index belongs to.
+    /// 取到索引对应元素所在的队列
+    pub fn queue_of(&self, index: usize) -> Option<QueueId> {
+        let node = self.slab.get(index)?;
+        self.owners.get(&node.owner).copied()
+    }
+
+    /// Removes and returns the element at index, whichever queue it belongs to.
+    /// 删除索引对应的元素，并返回该元素，不需要指定元素所在的队列；如果没有对应元素，则返回错误
+    pub fn remove(&mut self, index: usize) -> Result<T, DequeError> {
+        let qid = self.queue_of(index).ok_or(DequeError::NotFound(index))?;
+        self.queues[qid.0].remove_checked(index, &mut self.slab)
+    }
+
+    /// Removes and returns the element of the handle, whichever queue it belongs to.
+    /// 删除句柄对应的元素，并返回该元素，不需要指定元素所在的队列；如果元素已被删除（无论其索引是否被复用），都返回DequeError::Stale
+    pub fn remove_by_handle(&mut self, handle: Handle) -> Result<T, DequeError> {
+        let index = handle.index();
+        let qid = self.queue_of(index).ok_or(DequeError::Stale(index))?;
+        self.queues[qid.0].remove_by_handle(handle, &mut self.slab)
+    }
+
+    /// Moves the element at index to the back of the queue qid in O(1), the index of the element is unchanged.
+    /// 将索引对应的元素移动到队列qid的尾部，元素索引保持不变；如果没有对应元素或队列qid不存在，则返回错误
+    pub fn move_to(&mut self, index: usize, qid: QueueId) -> Result<(), DequeError> {
+        if qid.0 >= self.queues.len() {
+            return Err(DequeError::NoQueue(qid.0));
+        }
+        let from = self.queue_of(index).ok_or(DequeError::NotFound(index))?;
+        let (src, dst) = match from.0.cmp(&qid.0) {
+            std::cmp::Ordering::Equal => return self.queues[qid.0].move_to_back(index, &mut self.slab),
+            std::cmp::Ordering::Less => {
+                let (l, r) = self.queues.split_at_mut(qid.0);
+                (&mut l[from.0], &mut r[0])
+            },
+            std::cmp::Ordering::Greater => {
+                let (l, r) = self.queues.split_at_mut(from.0);
+                (&mut r[0], &mut l[qid.0])
+            },
+        };
+        src.transfer_to_back(index, dst, &mut self.slab)
+    }
+
+    /// Returns a reference to the element at index.
+    /// 取到索引对应元素的引用，如果没有对应元素，则返回None
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.slab.get(index).map(|node| &node.elem)
+    }
+
+    /// Returns a reference to the element of the handle.
+    /// 取到句柄对应元素的引用，如果元素已被删除（即使其索引被复用），则返回None
+    pub fn get_by_handle(&self, handle: Handle) -> Option<&T> {
+        match self.slab.get(handle.index()) {
+            Some(node) if node.gen == handle.gen() => Some(&node.elem),
+            _ => None,
+        }
+    }
+
+    /// Returns a mutable reference to the element at index.
+    /// 取到索引对应元素的可变引用，如果没有对应元素，则返回None
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.slab.get_mut(index).map(|node| &mut node.elem)
+    }
+
+    /// 取到队列的元素个数
+    pub fn len(&self, qid: QueueId) -> usize {
+        self.queues[qid.0].len()
+    }
+
+    /// 队列是否为空
+    pub fn is_empty(&self, qid: QueueId) -> bool {
+        self.queues[qid.0].is_empty()
+    }
+
+    /// 所有队列的元素总数
+    pub fn total_len(&self) -> usize {
+        self.slab.len()
+    }
+
+    /// 清空队列
+    pub fn clear(&mut self, qid: QueueId) {
+        self.queues[qid.0].clear(&mut self.slab);
+    }
+
+    /// 创建队列的迭代器
+    pub fn iter(&self, qid: QueueId) -> Iter<'_, T, Slab<Node<T>>> {
+        self.queues[qid.0].iter(&self.slab)
+    }
+}
+
+impl<T: Debug> Debug for MultiDeque<T> {
+    fn fmt(&self, f: &mut Formatter) -> FResult {
+        f.debug_struct("MultiDeque")
+            .field("queues", &self.queues)
+            .field("slab", &self.slab)
+            .finish()
+    }
+}
+
+#[test]
+fn test_multi(){
+    let mut multi: MultiDeque<u32> = MultiDeque::new();
+    let q1 = multi.add_queue();
+    let q2 = multi.add_queue();
+    let a = multi.push_back(q1, 1);
+    let b = multi.push_back(q1, 2);
+    let c = multi.push_front(q2, 3);
+    assert_eq!((multi.len(q1), multi.len(q2), multi.total_len()), (2, 1, 3));
+    assert_eq!(multi.queue_of(c), Some(q2));
+
+    // 移动后索引不变，所属队列改变
+    multi.move_to(a, q2).unwrap();
+    multi.move_to(c, q1).unwrap();
+    multi.move_to(b, q1).unwrap();
+    assert_eq!(multi.iter(q1).copied().collect::<Vec<u32>>(), vec![3, 2]);
+    assert_eq!(multi.iter(q2).copied().collect::<Vec<u32>>(), vec![1]);
+    assert_eq!(multi.queue_of(a), Some(q2));
+
+    assert_eq!(multi.remove(b), Ok(2));
+    assert_eq!(multi.remove(b), Err(DequeError::NotFound(b)));
+    assert_eq!(multi.move_to(b, q2), Err(DequeError::NotFound(b)));
+    assert_eq!(multi.move_to(a, QueueId(2)), Err(DequeError::NoQueue(2)));
+    *multi.get_mut(a).unwrap() += 10;
+    assert_eq!(multi.pop_back(q2), Some(11));
+    assert!(multi.is_empty(q2));
+    multi.clear(q1);
+    assert_eq!(multi.total_len(), 0);
+
+    // 句柄在元素删除后失效，即使其索引被复用
+    let h = multi.push_back_handle(q1, 4);
+    multi.move_to(h.index(), q2).unwrap();
+    assert_eq!(multi.get_by_handle(h), Some(&4));
+    assert_eq!(multi.remove_by_handle(h), Ok(4));
+    assert_eq!(multi.remove_by_handle(h), Err(DequeError::Stale(h.index())));
+    let d = multi.push_back(q1, 5);
+    assert_eq!(d, h.index());
+    assert_eq!(multi.get_by_handle(h), None);
+    assert_eq!(multi.remove_by_handle(h), Err(DequeError::Stale(d)));
+}
@@ -0,0 +1,163 @@
+//! 多级优先队列
+//! 每个优先级是一个双端队列，所有优先级的队列共享同一个slab（见multi_deque），同一优先级内的元素先进先出
+//! 修改元素的优先级只是把节点重新链接到另一个优先级的队列中，元素索引保持不变
+//! 例如：pi_lib中的task_pool，任务的优先级改变后，仍然可以用原来的索引删除任务
+
+use std::fmt::{Debug, Formatter, Result as FResult};
+
+use pi_slab::Slab;
+use crate::deque::{ Node, DequeError, Iter };
+use crate::multi_deque::{ MultiDeque, QueueId };
+
+/// 多级优先队列，级别越大优先级越高
+pub struct PriorityDeque<T>{
+    multi: MultiDeque<T>,
+    // 每个级别对应的队列，下标即级别
+    levels: Vec<QueueId>,
+}
+
+impl<T> PriorityDeque<T> {
+    /// 创建一个有levels个优先级（0..levels）的队列
+    pub fn new(levels: usize) -> PriorityDeque<T> {
+        let mut multi = MultiDeque::new();
+        let levels = (0..levels).map(|_| multi.add_queue()).collect();
+        Self {
+            multi,
+            levels,
+        }
+    }
+
+    /// 优先级的个数
+    pub fn levels(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// Append an element to the back of the level. return a index
+    /// 在指定优先级的队列尾部压入一个元素，返回元素索引，如果level超出优先级的个数，将会panic（不希望panic时使用push_checked）
+    pub fn push(&mut self, level: usize, elem: T) -> usize {
+        self.multi.push_back(self.levels[level], elem)
+    }
+
+    /// Append an element to the back of the level. return a index, or an error if level is out of range.
+    /// 在指定优先级的队列尾部压入一个元素，返回元素索引；如果level超出优先级的个数，则返回错误
+    pub fn push_checked(&mut self, level: usize, elem: T) -> Result<usize, DequeError> {
+        let qid = *self.levels.get(level).ok_or(DequeError::NoQueue(level))?;
+        Ok(self.multi.push_back(qid, elem))
+    }
+
+    /// Removes the first element of the highest non-empty level and returns it with the level, or None if it is empty.
+    /// 从最高的非空优先级队列头部弹出一个元素，并返回其优先级，如果队列中没有元素，则返回None
+    pub fn pop_highest(&mut self) -> Option<(usize, T)> {
+        let level = self.highest()?;
+        self.multi.pop_front(self.levels[level]).map(|elem| (level, elem))
+    }
+
+    /// Returns the element that pop_highest will return, with its index and level.
+    /// 取到pop_highest将弹出的元素，及其索引和优先级
+    pub fn peek_highest(&self) -> Option<(usize, usize, &T)> {
+        let level = self.highest()?;
+        let index = self.multi.get_first(self.levels[level]);
+        self.multi.get(index).map(|elem| (index, level, elem))
+    }
+
+    /// Removes and returns the element at index in O(1).
+    /// 删除索引对应的元素，并返回该元素；如果没有对应元素，则返回错误
+    pub fn remove(&mut self, index: usize) -> Result<T, DequeError> {
+        self.multi.remove(index)
+    }
+
+    /// Changes the level of the element at index in O(1), the element is moved to the back of the new level, and its index is unchanged.
+    /// 修改元素的优先级，元素被移动到新优先级队列的尾部，元素索引保持不变；如果没有对应元素或level超出优先级的个数，则返回错误
+    pub fn change_priority(&mut self, index: usize, level: usize) -> Result<(), DequeError> {
+        let qid = *self.levels.get(level).ok_or(DequeError::NoQueue(level))?;
+        self.multi.move_to(index, qid)
+    }
+
+    /// Returns the level of the element at index.
+    /// 取到索引对应元素的优先级
+    pub fn level_of(&self, index: usize) -> Option<usize> {
+        self.multi.queue_of(index).map(|qid| qid.index())
+    }
+
+    /// Returns a reference to the element at index.
+    /// 取到索引对应元素的引用，如果没有对应元素，则返回None
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.multi.get(index)
+    }
+
+    /// Returns a mutable reference to the element at index.
+    /// 取到索引对应元素的可变引用，如果没有对应元素，则返回None
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.multi.get_mut(index)
+    }
+
+    /// 取到队列元素个数
+    pub fn len(&self) -> usize {
+        self.multi.total_len()
+    }
+
+    /// 队列是否为空
+    pub fn is_empty(&self) -> bool {
+        self.multi.total_len() == 0
+    }
+
+    /// 取到指定优先级的元素个数
+    pub fn len_of(&self, level: usize) -> usize {
+        self.multi.len(self.levels[level])
+    }
+
+    /// 清空队列
+    pub fn clear(&mut self) {
+        for qid in self.levels.iter() {
+            self.multi.clear(*qid);
+        }
+    }
+
+    /// 创建指定优先级的迭代器
+    pub fn iter_level(&self, level: usize) -> Iter<'_, T, Slab<Node<T>>> {
+        self.multi.iter(self.levels[level])
+    }
+
+    // 最高的非空优先级
+    fn highest(&self) -> Option<usize> {
+        self.levels.iter().rposition(|qid| !self.multi.is_empty(*qid))
+    }
+}
+
+impl<T: Debug> Debug for PriorityDeque<T> {
+    fn fmt(&self, f: &mut Formatter) -> FResult {
+        f.debug_struct("PriorityDeque")
+            .field("multi", &self.multi)
+            .finish()
+    }
+}
+
+#[test]
+fn test_priority(){
+    let mut deque: PriorityDeque<&str> = PriorityDeque::new(3);
+    let low = deque.push(0, "low");
+    let a = deque.push(1, "a");
+    let b = deque.push(1, "b");
+    let high = deque.push(2, "high");
+    assert_eq!(deque.len(), 4);
+    assert_eq!(deque.peek_highest(), Some((high, 2, &"high")));
+
+    // 修改优先级后索引不变
+    deque.change_priority(low, 2).unwrap();
+    deque.change_priority(a, 1).unwrap();
+    assert_eq!(deque.level_of(low), Some(2));
+    assert_eq!(deque.iter_level(1).copied().collect::<Vec<&str>>(), vec!["b", "a"]);
+    assert_eq!(deque.remove(b), Ok("b"));
+    assert_eq!(deque.change_priority(b, 0), Err(DequeError::NotFound(b)));
+    // 超出范围的优先级返回错误，而不是panic
+    assert_eq!(deque.change_priority(a, 3), Err(DequeError::NoQueue(3)));
+    assert_eq!(deque.push_checked(3, "bad"), Err(DequeError::NoQueue(3)));
+    assert_eq!(deque.level_of(a), Some(1));
+
+    assert_eq!(deque.pop_highest(), Some((2, "high")));
+    assert_eq!(deque.pop_highest(), Some((2, "low")));
+    assert_eq!(deque.get(a), Some(&"a"));
+    assert_eq!(deque.pop_highest(), Some((1, "a")));
+    assert_eq!(deque.pop_highest(), None);
+    assert!(deque.is_empty());
+}
@@ -0,0 +1,346 @@
+//! 带顺序统计的双端队列
+//! 在slab_deque的基础上，为队列的节点额外维护一棵以队列位置为隐式键的树堆（treap）
+//! 使得按位置取元素（nth）和取元素的位置（position_of）都是O(log n)的，代价是插入和删除也变为O(log n)
+//! 当你只需要在队列两端操作，或很少查询位置时，请使用slab_deque
+
+use std::fmt::{Debug, Formatter, Result as FResult};
+
+use crate::deque::DequeError;
+use crate::slab_deque::{ SlabDeque, Iter };
+
+/// 树堆的节点，与队列的节点使用相同的索引，索引0表示空节点
+#[derive(Debug, Clone, Copy, Default)]
+struct RankNode {
+    parent: usize,
+    left: usize,
+    right: usize,
+    size: usize,
+    priority: u32,
+}
+
+/// 支持O(log n)按位置查询的双端队列
+pub struct RankedDeque<T>{
+    deque: SlabDeque<T>,
+    tree: Vec<RankNode>,
+    root: usize,
+    seed: u32,
+}
+
+impl<T> Default for RankedDeque<T> {
+    fn default() -> RankedDeque<T> {
+        RankedDeque::new()
+    }
+}
+
+impl<T> RankedDeque<T> {
+    pub fn new() -> RankedDeque<T> {
+        Self {
+            deque: SlabDeque::new(),
+            tree: vec![RankNode::default()],
+            root: 0,
+            seed: 0x9E37_79B9,
+        }
+    }
+
+    /// Append an element to the RankedDeque. return a index
+    /// 在尾部压入一个元素，返回元素索引
+    pub fn push_back(&mut self, elem: T) -> usize {
+        let index = self.deque.push_back(elem);
+        self.insert_at(self.size(self.root), index);
+        index
+    }
+
+    /// Prepend an element to the RankedDeque. return a index
+    /// 在头部压入一个元素，返回元素索引
+    pub fn push_front(&mut self, elem: T) -> usize {
+        let index = self.deque.push_front(elem);
+        self.insert_at(0, index);
+        index
+    }
+
+    /// Inserts an element after the anchor element. return a index
+    /// 在锚点元素之后插入一个元素，返回元素索引；如果锚点不是本队列的元素，则返回错误
+    pub fn insert_after(&mut self, anchor: usize, elem: T) -> Result<usize, DequeError> {
+        let index = self.deque.insert_after(anchor, elem)?;
+        self.insert_at(self.rank(anchor) + 1, index);
+        Ok(index)
+    }
+
+    /// Inserts an element before the anchor element. return a index
+    /// 在锚点元素之前插入一个元素，返回元素索引；如果锚点不是本队列的元素，则返回错误
+    pub fn insert_before(&mut self, anchor: usize, elem: T) -> Result<usize, DequeError> {
+        let index = self.deque.insert_before(anchor, elem)?;
+        self.insert_at(self.rank(anchor), index);
+        Ok(index)
+    }
+
+    /// Removes the first element from the RankedDeque and returns it, or None if it is empty.
+    /// 从队列头部弹出一个元素，如果队列中没有元素，则返回None
+    pub fn pop_front(&mut self) -> Option<T> {
+        let index = self.deque.nth(0)?;
+        self.remove_from_tree(index);
+        self.deque.pop_front()
+    }
+
+    /// Removes the last element from the RankedDeque and returns it, or None if it is empty.
+    /// 从队列尾部弹出一个元素，如果队列中没有元素，则返回None
+    pub fn pop_back(&mut self) -> Option<T> {
+        let index = self.deque.nth(self.deque.len().checked_sub(1)?)?;
+        self.remove_from_tree(index);
+        self.deque.pop_back()
+    }
+
+    /// Removes and returns the element at index from the RankedDeque.
+    /// 删除索引对应的元素，并返回该元素；如果没有对应元素，则返回错误
+    pub fn remove(&mut self, index: usize) -> Result<T, DequeError> {
+        let elem = self.deque.remove_checked(index)?;
+        self.remove_from_tree(index);
+        Ok(elem)
+    }
+
+    /// Returns the index of the n-th element from the front (starting from 0) in O(log n).
+    /// 取到队列中第n个元素的索引（从0开始），如果n超出队列长度，则返回None
+    pub fn nth(&self, mut n: usize) -> Option<usize> {
+        if n >= self.size(self.root) {
+            return None;
+        }
+        let mut node = self.root;
+        loop {
+            let left = self.tree[node].left;
+            let left_size = self.size(left);
+            if n < left_size {
+                node = left;
+            } else if n == left_size {
+                return Some(node);
+            } else {
+                n -= left_size + 1;
+                node = self.tree[node].right;
+            }
+        }
+    }
+
+    /// Returns the distance from the front of the RankedDeque to the element at index in O(log n).
+    /// 取到索引对应元素在队列中的位置（从0开始），如果没有对应元素，则返回None
+    pub fn position_of(&self, index: usize) -> Option<usize> {
+        self.deque.get(index).map(|_| self.rank(index))
+    }
+
+    /// Returns a reference to the element at index.
+    /// 取到索引对应元素的引用，如果没有对应元素，则返回None
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.deque.get(index)
+    }
+
+    /// Returns a mutable reference to the element at index.
+    /// 取到索引对应元素的可变引用，如果没有对应元素，则返回None
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.deque.get_mut(index)
+    }
+
+    /// Returns a reference to the first element, or None if the RankedDeque is empty.
+    /// 取到队列头部元素的引用，如果队列中没有元素，则返回None
+    pub fn front(&self) -> Option<&T> {
+        self.deque.front()
+    }
+
+    /// Returns a reference to the last element, or None if the RankedDeque is empty.
+    /// 取到队列尾部元素的引用，如果队列中没有元素，则返回None
+    pub fn back(&self) -> Option<&T> {
+        self.deque.back()
+    }
+
+    /// clear RankedDeque
+    /// 清空队列
+    pub fn clear(&mut self) {
+        self.deque.clear();
+        self.tree.truncate(1);
+        self.root = 0;
+    }
+
+    /// 取到队列元素个数
+    pub fn len(&self) -> usize {
+        self.deque.len()
+    }
+
+    /// 队列是否为空
+    pub fn is_empty(&self) -> bool {
+        self.deque.is_empty()
+    }
+
+    /// 创建队列的迭代器
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.deque.iter()
+    }
+
+    #[inline]
+    fn size(&self, node: usize) -> usize {
+        match node {
+            0 => 0,
+            _ => self.tree[node].size,
+        }
+    }
+
+    // 重新计算node的size，并修正其子节点的parent
+    fn update(&mut self, node: usize) {
+        let RankNode { left, right, .. } = self.tree[node];
+        self.tree[node].size = self.size(left) + self.size(right) + 1;
+        if left != 0 {
+            self.tree[left].parent = node;
+        }
+        if right != 0 {
+            self.tree[right].parent = node;
+        }
+    }
+
+    // 将以node为根的树分为前count个节点和剩余节点两棵树
+    fn split(&mut self, node: usize, count: usize) -> (usize, usize) {
+        if node == 0 {
+            return (0, 0);
+        }
+        let left = self.tree[node].left;
+        let left_size = self.size(left);
+        if count <= left_size {
+            let (l, r) = self.split(left, count);
+            self.tree[node].left = r;
+            self.update(node);
+            (l, node)
+        } else {
+            let right = self.tree[node].right;
+            let (l, r) = self.split(right, count - left_size - 1);
+            self.tree[node].right = l;
+            self.update(node);
+            (node, r)
+        }
+    }
+
+    // 将两棵树按先a后b的顺序合并
+    fn merge(&mut self, a: usize, b: usize) -> usize {
+        if a == 0 {
+            return b;
+        }
+        if b == 0 {
+            return a;
+        }
+        if self.tree[a].priority > self.tree[b].priority {
+            let right = self.tree[a].right;
+            self.tree[a].right = self.merge(right, b);
+            self.update(a);
+            a
+        } else {
+            let left = self.tree[b].left;
+            self.tree[b].left = self.merge(a, left);
+            self.update(b);
+            b
+        }
+    }
+
+    fn set_root(&mut self, root: usize) {
+        self.root = root;
+        if root != 0 {
+            self.tree[root].parent = 0;
+        }
+    }
+
+    // 将index对应的节点插入到树中position的位置
+    fn insert_at(&mut self, position: usize, index: usize) {
+        if index >= self.tree.len() {
+            self.tree.resize(index + 1, RankNode::default());
+        }
+        // xorshift32，仅用于树堆的随机优先级
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 17;
+        self.seed ^= self.seed << 5;
+        self.tree[index] = RankNode { parent: 0, left: 0, right: 0, size: 1, priority: self.seed };
+
+        let (l, r) = self.split(self.root, position);
+        let l = self.merge(l, index);
+        let root = self.merge(l, r);
+        self.set_root(root);
+    }
+
+    fn remove_from_tree(&mut self, index: usize) {
+        let position = self.rank(index);
+        let (l, r) = self.split(self.root, position);
+        let (_, r) = self.split(r, 1);
+        let root = self.merge(l, r);
+        self.set_root(root);
+    }
+
+    // 取到节点在树中的位置，即其之前的节点个数
+    fn rank(&self, index: usize) -> usize {
+        let mut rank = self.size(self.tree[index].left);
+        let mut node = index;
+        loop {
+            let parent = self.tree[node].parent;
+            if parent == 0 {
+                return rank;
+            }
+            if self.tree[parent].right == node {
+                rank += self.size(self.tree[parent].left) + 1;
+            }
+            node = parent;
+        }
+    }
+}
+
+impl<T: Debug> Debug for RankedDeque<T> {
+    fn fmt(&self, f: &mut Formatter) -> FResult {
+        f.debug_struct("RankedDeque")
+            .field("deque", &self.deque)
+            .field("root", &self.root)
+            .finish()
+    }
+}
+
+#[test]
+fn test_rank(){
+    let mut ranked: RankedDeque<u32> = RankedDeque::new();
+    let mut model: Vec<usize> = Vec::new();
+    let mut seed: u32 = 7;
+    for i in 0..2000 {
+        seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+        let r = (seed >> 16) as usize;
+        match r % 7 {
+            0 | 1 => model.push(ranked.push_back(i)),
+            2 => model.insert(0, ranked.push_front(i)),
+            3 if !model.is_empty() => {
+                let p = r % model.len();
+                let index = ranked.insert_after(model[p], i).unwrap();
+                model.insert(p + 1, index);
+            },
+            4 if !model.is_empty() => {
+                let p = r % model.len();
+                let index = ranked.insert_before(model[p], i).unwrap();
+                model.insert(p, index);
+            },
+            5 if !model.is_empty() => {
+                let p = r % model.len();
+                ranked.remove(model.remove(p)).unwrap();
+            },
+            6 if !model.is_empty() => {
+                if r & 1 == 0 {
+                    ranked.pop_front().unwrap();
+                    model.remove(0);
+                } else {
+                    ranked.pop_back().unwrap();
+                    model.pop();
+                }
+            },
+            _ => (),
+        }
+    }
+
+    assert_eq!(ranked.len(), model.len());
+    for (i, index) in model.iter().enumerate() {
+        assert_eq!(ranked.nth(i), Some(*index));
+        assert_eq!(ranked.position_of(*index), Some(i));
+    }
+    assert_eq!(ranked.nth(model.len()), None);
+    let elems: Vec<u32> = model.iter().map(|index| *ranked.get(*index).unwrap()).collect();
+    assert_eq!(ranked.iter().copied().collect::<Vec<u32>>(), elems);
+
+    ranked.clear();
+    assert_eq!(ranked.nth(0), None);
+    let index = ranked.push_back(1);
+    assert_eq!(ranked.position_of(index), Some(0));
+}
@@ -0,0 +1,154 @@
+//! 轮询调度队列
+//! 在slab_deque上维护一个游标，每次轮询返回游标所在的元素，并将游标移动到下一个元素（到达尾部后回到头部）
+//! 与pop_front后再push_back相比，轮询不会删除和重新插入元素，因此元素索引始终保持不变
+//! 例如：pi_lib中的task_pool，可以用本队列公平的轮询各个任务队列
+
+use std::fmt::{Debug, Formatter, Result as FResult};
+
+use crate::slab_deque::{ SlabDeque, Iter };
+
+/// 轮询调度队列
+pub struct RoundRobin<T>{
+    deque: SlabDeque<T>,
+    // 下一次轮询将返回的元素的索引，0表示队列头部
+    cursor: usize,
+}
+
+impl<T> Default for RoundRobin<T> {
+    fn default() -> RoundRobin<T> {
+        RoundRobin::new()
+    }
+}
+
+impl<T> RoundRobin<T> {
+    pub fn new() -> RoundRobin<T> {
+        Self {
+            deque: SlabDeque::new(),
+            cursor: 0,
+        }
+    }
+
+    /// Adds an element to the end of the current round. return a index
+    /// 插入一个元素，新元素在本轮中最后被轮询到，返回元素索引
+    pub fn push(&mut self, elem: T) -> usize {
+        match self.cursor {
+            0 => self.deque.push_back(elem),
+            // 游标始终指向本队列的元素
+            cursor => self.deque.insert_before(cursor, elem).unwrap(),
+        }
+    }
+
+    /// Returns the current element with its index, and advances the cursor, or None if it is empty.
+    /// 返回游标所在的元素及其索引，并将游标移动到下一个元素，效果上相当于把该元素移动到了本轮的末尾
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<(usize, &mut T)> {
+        let current = self.current()?;
+        self.cursor = self.deque.next_of(current).unwrap_or(0);
+        self.deque.get_mut(current).map(|elem| (current, elem))
+    }
+
+    /// Returns the element that next() will return, without advancing the cursor.
+    /// 取到下一次轮询将返回的元素及其索引，不移动游标
+    pub fn peek(&self) -> Option<(usize, &T)> {
+        let current = self.current()?;
+        self.deque.get(current).map(|elem| (current, elem))
+    }
+
+    /// Removes and returns the element at index, the cursor is moved to the next element if it is removed.
+    /// 删除索引对应的元素，并返回该元素；如果游标在该元素上，则游标移动到下一个元素
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        if index == self.cursor {
+            self.cursor = self.deque.next_of(index).unwrap_or(0);
+        }
+        self.deque.try_remove(index)
+    }
+
+    /// Returns a reference to the element at index.
+    /// 取到索引对应元素的引用，如果没有对应元素，则返回None
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.deque.get(index)
+    }
+
+    /// Returns a mutable reference to the element at index.
+    /// 取到索引对应元素的可变引用，如果没有对应元素，则返回None
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.deque.get_mut(index)
+    }
+
+    /// clear RoundRobin
+    /// 清空队列
+    pub fn clear(&mut self) {
+        self.deque.clear();
+        self.cursor = 0;
+    }
+
+    /// 取到队列元素个数
+    pub fn len(&self) -> usize {
+        self.deque.len()
+    }
+
+    /// 队列是否为空
+    pub fn is_empty(&self) -> bool {
+        self.deque.is_empty()
+    }
+
+    /// 创建队列的迭代器，按元素在底层队列中的顺序迭代，与游标位置无关
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.deque.iter()
+    }
+
+    fn current(&self) -> Option<usize> {
+        match (self.cursor, self.deque.get_first()) {
+            (_, 0) => None,
+            (0, first) => Some(first),
+            (cursor, _) => Some(cursor),
+        }
+    }
+}
+
+impl<T: Debug> Debug for RoundRobin<T> {
+    fn fmt(&self, f: &mut Formatter) -> FResult {
+        f.debug_struct("RoundRobin")
+            .field("deque", &self.deque)
+            .field("cursor", &self.cursor)
+            .finish()
+    }
+}
+
+#[test]
+fn test_round_robin(){
+    let mut rr: RoundRobin<char> = RoundRobin::new();
+    assert!(rr.next().is_none());
+    let a = rr.push('a');
+    let b = rr.push('b');
+    let c = rr.push('c');
+
+    let mut order = Vec::new();
+    for _ in 0..4 {
+        order.push(*rr.next().unwrap().1);
+    }
+    assert_eq!(order, vec!['a', 'b', 'c', 'a']);
+
+    // 新元素在本轮的最后，即a之后、b之前
+    let d = rr.push('d');
+    assert_eq!(rr.peek(), Some((b, &'b')));
+
+    // 删除游标所在的元素，游标移动到下一个元素
+    assert_eq!(rr.remove(b), Some('b'));
+    assert_eq!(rr.next(), Some((c, &mut 'c')));
+    assert_eq!(rr.next(), Some((a, &mut 'a')));
+    assert_eq!(rr.next(), Some((d, &mut 'd')));
+    assert_eq!(rr.remove(c), Some('c'));
+    assert_eq!(rr.next().map(|(i, _)| i), Some(a));
+    assert_eq!(rr.next().map(|(i, _)| i), Some(d));
+    assert_eq!(rr.len(), 2);
+
+    // 删除最后一个元素时，游标回到头部
+    assert_eq!(rr.remove(a), Some('a'));
+    assert_eq!(rr.remove(d), Some('d'));
+    assert_eq!(rr.remove(d), None);
+    assert!(rr.next().is_none());
+    let e = rr.push('e');
+    assert_eq!(rr.next(), Some((e, &mut 'e')));
+    assert_eq!(rr.next(), Some((e, &mut 'e')));
+}
@@ -3,9 +3,10 @@
 //! 如果你不满意使用Slab作为索引工厂，你也可以参照本模块，重新封装deque模块
 
 use std::fmt::{Debug, Formatter, Result as FResult};
+use std::cmp::Ordering;
 
 use pi_slab::Slab;
-use crate::deque::{ Deque, Node, Iter as DIter };
+use crate::deque::{ Deque, DequeError, CorruptionError, Handle, Node, SearchFrom, Iter as DIter, IterMut as DIterMut, ExtractIf as DExtractIf, Drain as DDrain };
 
 /// 一个用slab作为索引工厂的双端队列
 pub struct SlabDeque<T>{
@@ -19,6 +20,31 @@ impl<T> Default for SlabDeque<T> {
     }
 }
 
+/// 深复制队列，复制出的队列中每个元素的索引和句柄都与原队列相同
+impl<T: Clone> Clone for SlabDeque<T> {
+    fn clone(&self) -> SlabDeque<T> {
+        // Slab::clone会复制空位上的无效数据，因此逐个插入节点，并用占位节点填充空位，使索引保持不变
+        let mut slab = Slab::new();
+        let mut holes = Vec::new();
+        let mut next = 1;
+        for (index, node) in self.slab.iter() {
+            while next < index {
+                holes.push(slab.insert(node.clone()));
+                next += 1;
+            }
+            slab.insert(node.clone());
+            next += 1;
+        }
+        for hole in holes.into_iter().rev() {
+            slab.remove(hole);
+        }
+        Self {
+            deque: self.deque.clone_header(&mut slab),
+            slab,
+        }
+    }
+}
+
 impl<T> SlabDeque<T> {
     pub fn new() -> SlabDeque<T> {
         Self {
@@ -40,6 +66,142 @@ impl<T> SlabDeque<T> {
         self.deque.push_front(elem, &mut self.slab)
     }
 
+    /// 取到队列头部元素的索引，如果队列中没有元素，则返回0
+    pub fn get_first(&self) -> usize {
+        self.deque.get_first()
+    }
+
+    /// 取到队列尾部元素的索引，如果队列中没有元素，则返回0
+    pub fn get_last(&self) -> usize {
+        self.deque.get_last()
+    }
+
+    /// Returns the index of the element after the element at index.
+    /// 取到索引对应元素的下一个元素的索引，如果没有下一个元素或索引不是本队列的元素，则返回None
+    pub fn next_of(&self, index: usize) -> Option<usize> {
+        self.deque.next_of(index, &self.slab)
+    }
+
+    /// Returns the index of the element before the element at index.
+    /// 取到索引对应元素的上一个元素的索引，如果没有上一个元素或索引不是本队列的元素，则返回None
+    pub fn pre_of(&self, index: usize) -> Option<usize> {
+        self.deque.pre_of(index, &self.slab)
+    }
+
+    /// Returns the index of the n-th element from the front (starting from 0).
+    /// 取到队列中第n个元素的索引（从0开始），如果n超出队列长度，则返回None
+    pub fn nth(&self, n: usize) -> Option<usize> {
+        self.deque.nth(n, &self.slab)
+    }
+
+    /// Returns the distance from the front of the SlabDeque to the element at index.
+    /// 取到索引对应元素在队列中的位置（从0开始），如果没有对应元素，则返回None
+    pub fn position_of(&self, index: usize) -> Option<usize> {
+        self.deque.position_of(index, &self.slab)
+    }
+
+    /// Returns true if the element at a comes before the element at b in O(1).
+    /// 判断索引a对应的元素是否在索引b对应的元素之前，如果有索引不是本队列的元素，则返回错误
+    pub fn precedes(&mut self, a: usize, b: usize) -> Result<bool, DequeError> {
+        self.deque.precedes(a, b, &mut self.slab)
+    }
+
+    /// Returns the handle of the element at index.
+    /// 取到索引对应元素的句柄，如果没有对应元素，则返回None
+    pub fn handle(&mut self, index: usize) -> Option<Handle> {
+        self.deque.handle(index, &mut self.slab)
+    }
+
+    /// Returns true if the element of the handle is still in the SlabDeque.
+    /// 句柄对应的元素是否仍在队列中
+    pub fn contains_handle(&self, handle: Handle) -> bool {
+        self.deque.contains_handle(handle, &self.slab)
+    }
+
+    /// Returns a reference to the element of the handle.
+    /// 取到句柄对应元素的引用，如果句柄已失效，则返回None
+    pub fn get_by_handle(&self, handle: Handle) -> Option<&T> {
+        self.deque.get_by_handle(handle, &self.slab)
+    }
+
+    /// Returns a mutable reference to the element of the handle.
+    /// 取到句柄对应元素的可变引用，如果句柄已失效，则返回None
+    pub fn get_mut_by_handle(&mut self, handle: Handle) -> Option<&mut T> {
+        self.deque.get_mut_by_handle(handle, &mut self.slab)
+    }
+
+    /// Removes and returns the element of the handle.
+    /// 删除句柄对应的元素，并返回该元素；如果句柄已失效，则返回错误
+    pub fn remove_by_handle(&mut self, handle: Handle) -> Result<T, DequeError> {
+        self.deque.remove_by_handle(handle, &mut self.slab)
+    }
+
+    /// Removes and returns the element of the handle, or None if the handle is stale.
+    /// 删除句柄对应的元素，并返回该元素；如果句柄已失效，则返回None
+    pub fn try_remove_by_handle(&mut self, handle: Handle) -> Option<T> {
+        self.deque.try_remove_by_handle(handle, &mut self.slab)
+    }
+
+    /// Append an element to the SlabDeque. return the handle of the element
+    /// 在尾部压入一个元素，返回元素的句柄
+    pub fn push_back_handle(&mut self, elem: T) -> Handle {
+        self.deque.push_back_handle(elem, &mut self.slab)
+    }
+
+    /// Prepend an element to the SlabDeque. return the handle of the element
+    /// 在头部压入一个元素，返回元素的句柄
+    pub fn push_front_handle(&mut self, elem: T) -> Handle {
+        self.deque.push_front_handle(elem, &mut self.slab)
+    }
+
+    /// Inserts an element after the anchor element. return the handle of the element
+    /// 在锚点元素之后插入一个元素，返回元素的句柄；如果锚点句柄已失效，则返回错误
+    pub fn insert_after_handle(&mut self, anchor: Handle, elem: T) -> Result<Handle, DequeError> {
+        self.deque.insert_after_handle(anchor, elem, &mut self.slab)
+    }
+
+    /// Inserts an element before the anchor element. return the handle of the element
+    /// 在锚点元素之前插入一个元素，返回元素的句柄；如果锚点句柄已失效，则返回错误
+    pub fn insert_before_handle(&mut self, anchor: Handle, elem: T) -> Result<Handle, DequeError> {
+        self.deque.insert_before_handle(anchor, elem, &mut self.slab)
+    }
+
+    /// Inserts an element after the anchor element. return a index
+    /// 在锚点元素之后插入一个元素，返回元素索引；如果锚点不是本队列的元素，则返回错误
+    pub fn insert_after(&mut self, anchor: usize, elem: T) -> Result<usize, DequeError> {
+        self.deque.insert_after(anchor, elem, &mut self.slab)
+    }
+
+    /// Inserts an element before the anchor element. return a index
+    /// 在锚点元素之前插入一个元素，返回元素索引；如果锚点不是本队列的元素，则返回错误
+    pub fn insert_before(&mut self, anchor: usize, elem: T) -> Result<usize, DequeError> {
+        self.deque.insert_before(anchor, elem, &mut self.slab)
+    }
+
+    /// Moves the element at index to the front of the SlabDeque, the index of the element is unchanged.
+    /// 将索引对应的元素移动到队列头部，元素索引不变
+    pub fn move_to_front(&mut self, index: usize) -> Result<(), DequeError> {
+        self.deque.move_to_front(index, &mut self.slab)
+    }
+
+    /// Moves the element at index to the back of the SlabDeque, the index of the element is unchanged.
+    /// 将索引对应的元素移动到队列尾部，元素索引不变
+    pub fn move_to_back(&mut self, index: usize) -> Result<(), DequeError> {
+        self.deque.move_to_back(index, &mut self.slab)
+    }
+
+    /// Moves the element at index after the anchor element, the index of the element is unchanged.
+    /// 将索引对应的元素移动到锚点元素之后，元素索引不变
+    pub fn move_after(&mut self, index: usize, anchor: usize) -> Result<(), DequeError> {
+        self.deque.move_after(index, anchor, &mut self.slab)
+    }
+
+    /// Moves the element at index before the anchor element, the index of the element is unchanged.
+    /// 将索引对应的元素移动到锚点元素之前，元素索引不变
+    pub fn move_before(&mut self, index: usize, anchor: usize) -> Result<(), DequeError> {
+        self.deque.move_before(index, anchor, &mut self.slab)
+    }
+
     /// Removes the first element from the SlabDeque and returns it, or None if it is empty.
     /// 从队列头部弹出一个元素，并返回弹出的头部元素，如果队列中没有元素，则返回None
     pub fn pop_front(&mut self) -> Option<T> {
@@ -58,12 +220,112 @@ impl<T> SlabDeque<T> {
         self.deque.remove(index, &mut self.slab)
     }
 
+    /// Removes the first element from the SlabDeque and returns it, or Empty error if it is empty.
+    /// 从队列头部弹出一个元素，如果队列中没有元素，则返回错误
+    pub fn pop_front_checked(&mut self) -> Result<T, DequeError> {
+        self.deque.pop_front_checked(&mut self.slab)
+    }
+
+    /// Removes the last element from the SlabDeque and returns it, or Empty error if it is empty.
+    /// 从队列尾部弹出一个元素，如果队列中没有元素，则返回错误
+    pub fn pop_back_checked(&mut self) -> Result<T, DequeError> {
+        self.deque.pop_back_checked(&mut self.slab)
+    }
+
+    /// Removes and returns the element at index from the SlabDeque.
+    /// 删除索引对应的元素，并返回该元素；如果没有对应元素，则返回错误
+    pub fn remove_checked(&mut self, index: usize) -> Result<T, DequeError> {
+        self.deque.remove_checked(index, &mut self.slab)
+    }
+
     /// Removes and returns the element at index from the SlabDeque.
     /// 尝试删除索引对应的元素，并返回该元素；如果没有对应元素，则返回None
     pub fn try_remove(&mut self, index: usize) -> Option<T> {
         self.deque.try_remove(index, &mut self.slab)
     }
 
+    /// Retains only the elements specified by the predicate.
+    /// 只保留满足条件的元素，其它元素将被删除
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, f: F) {
+        self.deque.retain(f, &mut self.slab)
+    }
+
+    /// Retains only the elements specified by the predicate, the predicate can modify the elements.
+    /// 只保留满足条件的元素，其它元素将被删除，判断时可以修改元素
+    pub fn retain_mut<F: FnMut(&mut T) -> bool>(&mut self, f: F) {
+        self.deque.retain_mut(f, &mut self.slab)
+    }
+
+    /// Creates an iterator which removes the elements matching the predicate, yielding them with their old indexs.
+    /// 创建一个迭代器，删除满足条件的元素，并返回元素的原索引和元素本身
+    pub fn extract_if<F: FnMut(&mut T) -> bool>(&mut self, pred: F) -> ExtractIf<'_, T, F> {
+        ExtractIf{
+            d_iter: self.deque.extract_if(pred, &mut self.slab),
+        }
+    }
+
+    /// Rotates the SlabDeque n places to the left, n is taken modulo len.
+    /// 将队列向左旋转n个位置，即把头部的n个元素移动到尾部，元素索引不变
+    pub fn rotate_left(&mut self, n: usize) {
+        self.deque.rotate_left(n, &mut self.slab)
+    }
+
+    /// Rotates the SlabDeque n places to the right, n is taken modulo len.
+    /// 将队列向右旋转n个位置，即把尾部的n个元素移动到头部，元素索引不变
+    pub fn rotate_right(&mut self, n: usize) {
+        self.deque.rotate_right(n, &mut self.slab)
+    }
+
+    /// Reverses the order of the elements in place.
+    /// 反转队列，元素索引不变
+    pub fn reverse(&mut self) {
+        self.deque.reverse(&mut self.slab)
+    }
+
+    /// Swaps the positions of the elements at a and b.
+    /// 交换两个元素在队列中的位置，元素索引不变；如果有索引不是本队列的元素，则返回错误
+    pub fn swap(&mut self, a: usize, b: usize) -> Result<(), DequeError> {
+        self.deque.swap(a, b, &mut self.slab)
+    }
+
+    /// Sorts the SlabDeque, the sort is stable and indexs of the elements are unchanged.
+    /// 对队列进行稳定排序，排序后元素索引不变
+    pub fn sort(&mut self) where T: Ord {
+        self.deque.sort(&mut self.slab)
+    }
+
+    /// Sorts the SlabDeque with a comparator function, the sort is stable and indexs of the elements are unchanged.
+    /// 使用比较函数对队列进行稳定排序，排序后元素索引不变
+    pub fn sort_by<F: FnMut(&T, &T) -> Ordering>(&mut self, compare: F) {
+        self.deque.sort_by(compare, &mut self.slab)
+    }
+
+    /// Sorts the SlabDeque with a key extraction function, the sort is stable and indexs of the elements are unchanged.
+    /// 使用键函数对队列进行稳定排序，排序后元素索引不变
+    pub fn sort_by_key<K: Ord, F: FnMut(&T) -> K>(&mut self, f: F) {
+        self.deque.sort_by_key(f, &mut self.slab)
+    }
+
+    /// Inserts an element into a SlabDeque sorted by compare, return a index.
+    /// 在按compare排好序的队列中插入元素，元素插入到所有与其相等的元素之后，from指定从哪一端开始查找插入位置
+    pub fn insert_sorted_by<F: FnMut(&T, &T) -> Ordering>(&mut self, elem: T, compare: F, from: SearchFrom) -> usize {
+        self.deque.insert_sorted_by(elem, compare, from, &mut self.slab)
+    }
+
+    /// Creates a draining iterator that removes the elements and yields them from front to back.
+    /// 创建一个迭代器，从头到尾弹出队列中的元素；迭代器销毁时，剩余的元素也将被删除
+    pub fn drain(&mut self) -> Drain<'_, T> {
+        Drain{
+            d_iter: self.deque.drain(&mut self.slab),
+        }
+    }
+
+    /// Checks the link structure of the SlabDeque.
+    /// 检查队列的链表结构是否完整，通常在调试或测试中使用
+    pub fn validate(&self) -> Result<(), CorruptionError> {
+        self.deque.validate(&self.slab)
+    }
+
     /// clear SlabDeque
     /// 清空队列
     pub fn clear(&mut self) {
@@ -72,15 +334,63 @@ impl<T> SlabDeque<T> {
 
     /// 取到队列元素个数
     pub fn len(&self) -> usize {
-        self.slab.len()
+        self.deque.len()
+    }
+
+    /// 队列是否为空
+    pub fn is_empty(&self) -> bool {
+        self.deque.is_empty()
+    }
+
+    /// Returns a reference to the first element, or None if the SlabDeque is empty.
+    /// 取到队列头部元素的引用，如果队列中没有元素，则返回None
+    pub fn front(&self) -> Option<&T> {
+        self.deque.front(&self.slab)
+    }
+
+    /// Returns a mutable reference to the first element, or None if the SlabDeque is empty.
+    /// 取到队列头部元素的可变引用，如果队列中没有元素，则返回None
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.deque.front_mut(&mut self.slab)
+    }
+
+    /// Returns a reference to the last element, or None if the SlabDeque is empty.
+    /// 取到队列尾部元素的引用，如果队列中没有元素，则返回None
+    pub fn back(&self) -> Option<&T> {
+        self.deque.back(&self.slab)
+    }
+
+    /// Returns a mutable reference to the last element, or None if the SlabDeque is empty.
+    /// 取到队列尾部元素的可变引用，如果队列中没有元素，则返回None
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.deque.back_mut(&mut self.slab)
+    }
+
+    /// Returns a reference to the element at index.
+    /// 取到索引对应元素的引用，如果没有对应元素，则返回None
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.deque.get(index, &self.slab)
+    }
+
+    /// Returns a mutable reference to the element at index.
+    /// 取到索引对应元素的可变引用，如果没有对应元素，则返回None
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.deque.get_mut(index, &mut self.slab)
     }
 
     /// 创建队列的迭代器
-    pub fn iter(&mut self) -> Iter<T> {
+    pub fn iter(&self) -> Iter<'_, T> {
         Iter{
             d_iter: self.deque.iter(&self.slab),
         }
     }
+
+    /// 创建队列的可变迭代器
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut{
+            d_iter: self.deque.iter_mut(&mut self.slab),
+        }
+    }
 }
 
 impl<T: Debug> Debug for SlabDeque<T> {
@@ -103,6 +413,82 @@ impl<'a, T> Iterator for Iter<'a, T> {
     fn next(&mut self) -> Option<&'a T> {
         self.d_iter.next()
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.d_iter.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        self.d_iter.next_back()
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+pub struct IterMut<'a, T: 'a> {
+    d_iter: DIterMut<'a, T, Slab<Node<T>>>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        self.d_iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.d_iter.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<&'a mut T> {
+        self.d_iter.next_back()
+    }
+}
+
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {}
+
+pub struct Drain<'a, T: 'a> {
+    d_iter: DDrain<'a, T, Slab<Node<T>>>,
+}
+
+impl<'a, T> Iterator for Drain<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.d_iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.d_iter.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Drain<'a, T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.d_iter.next_back()
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Drain<'a, T> {}
+
+pub struct ExtractIf<'a, T: 'a, F: FnMut(&mut T) -> bool> {
+    d_iter: DExtractIf<'a, T, Slab<Node<T>>, F>,
+}
+
+impl<'a, T, F: FnMut(&mut T) -> bool> Iterator for ExtractIf<'a, T, F> {
+    type Item = (usize, T);
+
+    fn next(&mut self) -> Option<(usize, T)> {
+        self.d_iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.d_iter.size_hint()
+    }
 }
 
 #[cfg(test)]
@@ -118,6 +504,287 @@ fn test(){
 
 }
 
+#[test]
+fn test_peek(){
+    let mut fast_deque: SlabDeque<u32> = SlabDeque::new();
+    assert_eq!(fast_deque.front(), None);
+    assert_eq!(fast_deque.back(), None);
+
+    let i1 = fast_deque.push_back(1);
+    let i2 = fast_deque.push_back(2);
+    fast_deque.push_front(0);
+    assert_eq!(fast_deque.front(), Some(&0));
+    assert_eq!(fast_deque.back(), Some(&2));
+
+    *fast_deque.front_mut().unwrap() = 10;
+    *fast_deque.back_mut().unwrap() = 20;
+    *fast_deque.get_mut(i1).unwrap() += 10;
+    assert_eq!(fast_deque.pop_front(), Some(10));
+    assert_eq!(fast_deque.get(i1), Some(&11));
+    assert_eq!(fast_deque.get(i2), Some(&20));
+
+    fast_deque.remove(i2);
+    assert_eq!(fast_deque.get(i2), None);
+    assert_eq!(fast_deque.back(), Some(&11));
+}
+
+
+#[test]
+fn test_insert(){
+    let mut fast_deque: SlabDeque<u32> = SlabDeque::new();
+    let i1 = fast_deque.push_back(1);
+    let i3 = fast_deque.push_back(3);
+    fast_deque.insert_after(i1, 2).unwrap();
+    fast_deque.insert_before(i1, 0).unwrap();
+    fast_deque.insert_after(i3, 4).unwrap();
+    assert_eq!(fast_deque.iter().copied().collect::<Vec<u32>>(), vec![0, 1, 2, 3, 4]);
+
+    fast_deque.remove(i3);
+    assert_eq!(fast_deque.insert_after(i3, 5), Err(DequeError::NotFound(i3)));
+    assert_eq!(fast_deque.len(), 4);
+}
+
+#[test]
+fn test_move(){
+    let mut fast_deque: SlabDeque<u32> = SlabDeque::new();
+    let indexs: Vec<usize> = (0..4).map(|i| fast_deque.push_back(i)).collect();
+
+    fast_deque.move_to_front(indexs[2]).unwrap();
+    fast_deque.move_to_back(indexs[0]).unwrap();
+    assert_eq!(fast_deque.iter().copied().collect::<Vec<u32>>(), vec![2, 1, 3, 0]);
+    fast_deque.move_after(indexs[2], indexs[3]).unwrap();
+    fast_deque.move_before(indexs[0], indexs[1]).unwrap();
+    fast_deque.move_after(indexs[1], indexs[1]).unwrap();
+    assert_eq!(fast_deque.iter().copied().collect::<Vec<u32>>(), vec![0, 1, 3, 2]);
+    assert_eq!(fast_deque.iter().rev().copied().collect::<Vec<u32>>(), vec![2, 3, 1, 0]);
+    assert_eq!(fast_deque.get(indexs[2]), Some(&2));
+    assert_eq!(fast_deque.len(), 4);
+    fast_deque.validate().unwrap();
+}
+
+#[test]
+fn test_retain(){
+    let mut fast_deque: SlabDeque<u32> = SlabDeque::new();
+    let indexs: Vec<usize> = (0..10).map(|i| fast_deque.push_back(i)).collect();
+
+    fast_deque.retain(|elem| elem % 3 != 0);
+    assert_eq!(fast_deque.iter().copied().collect::<Vec<u32>>(), vec![1, 2, 4, 5, 7, 8]);
+
+    fast_deque.retain_mut(|elem| {
+        *elem += 1;
+        *elem != 9
+    });
+    assert_eq!(fast_deque.iter().copied().collect::<Vec<u32>>(), vec![2, 3, 5, 6, 8]);
+
+    let extracted: Vec<(usize, u32)> = fast_deque.extract_if(|elem| *elem % 2 == 0).collect();
+    assert_eq!(extracted, vec![(indexs[1], 2), (indexs[5], 6), (indexs[7], 8)]);
+    assert_eq!(fast_deque.iter().rev().copied().collect::<Vec<u32>>(), vec![5, 3]);
+    assert_eq!(fast_deque.len(), 2);
+    fast_deque.validate().unwrap();
+}
+
+#[test]
+fn test_drain(){
+    let mut fast_deque: SlabDeque<String> = SlabDeque::new();
+    for i in 0..5 {
+        fast_deque.push_back(i.to_string());
+    }
+
+    let mut drain = fast_deque.drain();
+    assert_eq!(drain.len(), 5);
+    assert_eq!(drain.next(), Some("0".to_string()));
+    assert_eq!(drain.next_back(), Some("4".to_string()));
+    assert_eq!(drain.next(), Some("1".to_string()));
+    drop(drain);
+    assert!(fast_deque.is_empty());
+    assert_eq!(fast_deque.len(), 0);
+
+    fast_deque.push_back("5".to_string());
+    assert_eq!(fast_deque.drain().collect::<Vec<String>>(), vec!["5".to_string()]);
+    assert_eq!(fast_deque.front(), None);
+}
+
+#[test]
+fn test_handle(){
+    let mut fast_deque: SlabDeque<u32> = SlabDeque::new();
+    let i1 = fast_deque.push_back(1);
+    let h1 = fast_deque.handle(i1).unwrap();
+    assert_eq!(h1.index(), i1);
+    assert_eq!(fast_deque.get_by_handle(h1), Some(&1));
+
+    assert_eq!(fast_deque.remove_by_handle(h1), Ok(1));
+    assert_eq!(fast_deque.remove_by_handle(h1), Err(DequeError::Stale(i1)));
+
+    // 被删除的索引将被复用，旧句柄不能访问到新元素
+    let i2 = fast_deque.push_back(2);
+    assert_eq!(i1, i2);
+    assert!(!fast_deque.contains_handle(h1));
+    assert_eq!(fast_deque.get_mut_by_handle(h1), None);
+    assert_eq!(fast_deque.remove_by_handle(h1), Err(DequeError::Stale(i1)));
+    assert_eq!(fast_deque.try_remove_by_handle(h1), None);
+    assert_eq!(fast_deque.front(), Some(&2));
+
+    // 直接返回句柄的插入
+    let h3 = fast_deque.push_back_handle(3);
+    let h0 = fast_deque.push_front_handle(0);
+    let h4 = fast_deque.insert_after_handle(h3, 4).unwrap();
+    fast_deque.insert_before_handle(h0, 5).unwrap();
+    assert_eq!(fast_deque.insert_after_handle(h1, 6), Err(DequeError::Stale(i1)));
+    assert_eq!(fast_deque.iter().copied().collect::<Vec<u32>>(), vec![5, 0, 2, 3, 4]);
+    assert_eq!(fast_deque.try_remove_by_handle(h4), Some(4));
+    assert_eq!(fast_deque.get_by_handle(h3), Some(&3));
+}
+
+#[test]
+fn test_checked(){
+    let mut fast_deque: SlabDeque<u32> = SlabDeque::new();
+    assert_eq!(fast_deque.pop_front_checked(), Err(DequeError::Empty));
+    assert_eq!(fast_deque.pop_back_checked(), Err(DequeError::Empty));
+    assert_eq!(fast_deque.remove_checked(1), Err(DequeError::NotFound(1)));
+
+    let i = fast_deque.push_back(1);
+    fast_deque.push_back(2);
+    assert_eq!(fast_deque.remove_checked(i), Ok(1));
+    assert_eq!(fast_deque.remove_checked(i), Err(DequeError::NotFound(i)));
+    assert_eq!(fast_deque.pop_back_checked(), Ok(2));
+    assert_eq!(fast_deque.pop_front_checked(), Err(DequeError::Empty));
+    assert_eq!(DequeError::Empty.to_string(), "deque is empty");
+}
+
+#[test]
+fn test_clone(){
+    let mut fast_deque: SlabDeque<String> = SlabDeque::new();
+    let indexs: Vec<usize> = (0..6).map(|i| fast_deque.push_back(i.to_string())).collect();
+    fast_deque.remove(indexs[0]);
+    fast_deque.remove(indexs[3]);
+    let h = fast_deque.handle(indexs[4]).unwrap();
+
+    let mut copy = fast_deque.clone();
+    copy.validate().unwrap();
+    assert_eq!(copy.len(), 4);
+    assert_eq!(copy.get(indexs[5]), Some(&"5".to_string()));
+    assert_eq!(copy.get_by_handle(h), Some(&"4".to_string()));
+    assert_eq!(copy.get(indexs[3]), None);
+
+    // 修改复制出的队列不影响原队列
+    copy.get_mut(indexs[1]).unwrap().push('1');
+    copy.push_back("6".to_string());
+    copy.remove(indexs[2]);
+    assert_eq!(copy.iter().cloned().collect::<Vec<String>>(), vec!["11", "4", "5", "6"]);
+    assert_eq!(fast_deque.iter().cloned().collect::<Vec<String>>(), vec!["1", "2", "4", "5"]);
+    fast_deque.validate().unwrap();
+}
+
+#[test]
+fn test_sort(){
+    let mut fast_deque: SlabDeque<(u32, u32)> = SlabDeque::new();
+    let values = [5, 3, 8, 3, 1, 9, 5, 0, 7, 3, 2];
+    let indexs: Vec<usize> = values.iter().enumerate().map(|(i, v)| fast_deque.push_back((*v, i as u32))).collect();
+
+    fast_deque.sort_by_key(|e| e.0);
+    fast_deque.validate().unwrap();
+    let mut expect: Vec<(u32, u32)> = values.iter().enumerate().map(|(i, v)| (*v, i as u32)).collect();
+    expect.sort_by_key(|e| e.0);
+    assert_eq!(fast_deque.iter().copied().collect::<Vec<(u32, u32)>>(), expect);
+    for (i, index) in indexs.iter().enumerate() {
+        assert_eq!(fast_deque.get(*index).unwrap().1, i as u32);
+    }
+
+    fast_deque.sort_by(|a, b| b.cmp(a));
+    expect.sort_by(|a, b| b.cmp(a));
+    assert_eq!(fast_deque.iter().copied().collect::<Vec<(u32, u32)>>(), expect);
+    expect.reverse();
+    assert_eq!(fast_deque.iter().rev().copied().collect::<Vec<(u32, u32)>>(), expect);
+    fast_deque.sort();
+    assert_eq!(fast_deque.iter().copied().collect::<Vec<(u32, u32)>>(), expect);
+    fast_deque.validate().unwrap();
+}
+
+#[test]
+fn test_insert_sorted(){
+    let mut fast_deque: SlabDeque<(u32, char)> = SlabDeque::new();
+    let items = [(5, 'a'), (3, 'b'), (8, 'c'), (5, 'd'), (1, 'e'), (8, 'f'), (0, 'g'), (5, 'h')];
+    for (i, item) in items.iter().enumerate() {
+        let from = if i % 2 == 0 { SearchFrom::Back } else { SearchFrom::Front };
+        let index = fast_deque.insert_sorted_by(*item, |a, b| a.0.cmp(&b.0), from);
+        assert_eq!(fast_deque.get(index), Some(item));
+    }
+    let mut expect = items.to_vec();
+    expect.sort_by_key(|e| e.0);
+    assert_eq!(fast_deque.iter().copied().collect::<Vec<(u32, char)>>(), expect);
+    fast_deque.validate().unwrap();
+}
+
+#[test]
+fn test_position(){
+    let mut fast_deque: SlabDeque<u32> = SlabDeque::new();
+    assert_eq!(fast_deque.nth(0), None);
+    let indexs: Vec<usize> = (0..7).map(|i| fast_deque.push_back(i)).collect();
+    for (i, index) in indexs.iter().enumerate() {
+        assert_eq!(fast_deque.nth(i), Some(*index));
+        assert_eq!(fast_deque.position_of(*index), Some(i));
+    }
+    assert_eq!(fast_deque.nth(7), None);
+    fast_deque.remove(indexs[3]);
+    assert_eq!(fast_deque.position_of(indexs[3]), None);
+    assert_eq!(fast_deque.position_of(indexs[4]), Some(3));
+    assert_eq!(fast_deque.nth(3), Some(indexs[4]));
+}
+
+#[test]
+fn test_rotate(){
+    let mut fast_deque: SlabDeque<u32> = SlabDeque::new();
+    let indexs: Vec<usize> = (0..7).map(|i| fast_deque.push_back(i)).collect();
+    let mut model: VecDeque<u32> = (0..7).collect();
+
+    for n in [1, 5, 7, 10, 0, 3] {
+        fast_deque.rotate_left(n);
+        model.rotate_left(n % 7);
+        assert_eq!(fast_deque.iter().copied().collect::<Vec<u32>>(), Vec::from(model.clone()));
+        fast_deque.validate().unwrap();
+        fast_deque.rotate_right(n + 1);
+        model.rotate_right((n + 1) % 7);
+        assert_eq!(fast_deque.iter().rev().copied().collect::<Vec<u32>>(), model.iter().rev().copied().collect::<Vec<u32>>());
+        fast_deque.validate().unwrap();
+    }
+
+    fast_deque.reverse();
+    model = model.into_iter().rev().collect();
+    assert_eq!(fast_deque.iter().copied().collect::<Vec<u32>>(), Vec::from(model.clone()));
+    fast_deque.validate().unwrap();
+
+    let (a, b) = (fast_deque.nth(0).unwrap(), fast_deque.nth(6).unwrap());
+    for (x, y) in [(a, b), (indexs[2], indexs[3]), (indexs[4], indexs[3]), (indexs[1], indexs[5]), (indexs[0], indexs[0])] {
+        let (px, py) = (fast_deque.position_of(x).unwrap(), fast_deque.position_of(y).unwrap());
+        fast_deque.swap(x, y).unwrap();
+        model.swap(px, py);
+        assert_eq!(fast_deque.iter().copied().collect::<Vec<u32>>(), Vec::from(model.clone()));
+        fast_deque.validate().unwrap();
+    }
+    for (i, index) in indexs.iter().enumerate() {
+        assert_eq!(fast_deque.get(*index), Some(&(i as u32)));
+    }
+}
+
+#[test]
+fn test_iter(){
+    let mut fast_deque: SlabDeque<u32> = SlabDeque::new();
+    for i in 0..5 {
+        fast_deque.push_back(i);
+    }
+
+    let mut iter = fast_deque.iter();
+    assert_eq!(iter.len(), 5);
+    assert_eq!(iter.next(), Some(&0));
+    assert_eq!(iter.next_back(), Some(&4));
+    assert_eq!(iter.len(), 3);
+    assert_eq!(iter.rev().copied().collect::<Vec<u32>>(), vec![3, 2, 1]);
+
+    for elem in fast_deque.iter_mut().rev().take(2) {
+        *elem += 10;
+    }
+    assert_eq!(fast_deque.iter().copied().collect::<Vec<u32>>(), vec![0, 1, 2, 13, 14]);
+}
 
 #[test]
 fn test_effict(){
@@ -0,0 +1,229 @@
+//! 分层时间轮
+//! 时间轮共有LEVELS层，每层有SLOTS个槽，第l层的一个槽覆盖SLOTS^l个时间单位，每个槽是multi_deque中的一个队列
+//! 定时器根据其到期时间与当前时间的最高不同位放入对应层的槽中，时间推进到某个槽时，其中的定时器要么到期，要么降级到更低的层
+//! 降级只是把节点重新链接到另一个槽的队列中，因此定时器的索引始终不变，取消定时器只需要一次O(1)的删除
+
+use std::fmt::{Debug, Formatter, Result as FResult};
+
+use crate::deque::{ DequeError, Handle };
+use crate::multi_deque::{ MultiDeque, QueueId };
+
+// 每层的槽数
+const SLOTS: usize = 64;
+// 每层占用的时间位数
+const SLOT_BITS: usize = 6;
+// 层数，足以覆盖u64的全部时间
+const LEVELS: usize = 11;
+// 到期时间不晚于当前时间的定时器所在的队列
+const READY: usize = LEVELS * SLOTS;
+
+/// 时间轮中的定时器
+#[derive(Debug)]
+struct Timer<T> {
+    deadline: u64,
+    elem: T,
+}
+
+/// 定时器的句柄，定时器到期或被取消后，句柄失效，即使其索引被复用
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimerHandle(Handle);
+
+impl TimerHandle {
+    /// 定时器的索引
+    pub fn index(&self) -> usize {
+        self.0.index()
+    }
+}
+
+/// 分层时间轮
+pub struct TimerWheel<T>{
+    multi: MultiDeque<Timer<T>>,
+    // 下标为 层 * SLOTS + 槽，最后一个是READY
+    slots: Vec<QueueId>,
+    // 每层中非空槽的位图
+    occupied: [u64; LEVELS],
+    // 当前时间，时间轮中所有定时器的到期时间都晚于当前时间（READY中的除外）
+    now: u64,
+}
+
+impl<T> TimerWheel<T> {
+    /// 创建一个当前时间为now的时间轮
+    pub fn new(now: u64) -> TimerWheel<T> {
+        let mut multi = MultiDeque::new();
+        let slots = (0..=READY).map(|_| multi.add_queue()).collect();
+        Self {
+            multi,
+            slots,
+            occupied: [0; LEVELS],
+            now,
+        }
+    }
+
+    /// 时间轮的当前时间
+    pub fn now(&self) -> u64 {
+        self.now
+    }
+
+    /// Schedules an element to expire at deadline. if the deadline is not later than now, it expires on the next advance.
+    /// 添加一个在deadline到期的定时器，返回定时器的句柄；如果deadline不晚于当前时间，则在下一次advance时到期
+    pub fn schedule(&mut self, deadline: u64, elem: T) -> TimerHandle {
+        let slot = self.slot_for(deadline);
+        let handle = self.multi.push_back_handle(self.slots[slot], Timer { deadline, elem });
+        self.occupy(slot);
+        TimerHandle(handle)
+    }
+
+    /// Cancels the timer in O(1) and returns its element.
+    /// 取消定时器，并返回其元素；如果定时器已到期或已被取消，则返回DequeError::Stale
+    pub fn cancel(&mut self, handle: TimerHandle) -> Result<T, DequeError> {
+        self.multi.remove_by_handle(handle.0).map(|timer| timer.elem)
+    }
+
+    /// Returns a reference to the element of the timer.
+    /// 取到定时器的元素，如果定时器已到期或已被取消，则返回None
+    pub fn get(&self, handle: TimerHandle) -> Option<&T> {
+        self.multi.get_by_handle(handle.0).map(|timer| &timer.elem)
+    }
+
+    /// Advances the time to now, and returns the expired elements in the order of their deadlines,
+    /// except that elements scheduled with a past deadline come first, in the order they were scheduled.
+    /// 将当前时间推进到now，按到期时间的顺序返回所有到期（到期时间不晚于now）的元素
+    /// 添加时已经过期的元素排在最前面，按添加的顺序返回
+    pub fn advance(&mut self, now: u64) -> Vec<T> {
+        let mut expired = Vec::new();
+        while let Some(timer) = self.multi.pop_front(self.slots[READY]) {
+            expired.push(timer.elem);
+        }
+        while let Some((level, slot, start)) = self.next_slot() {
+            if start > now {
+                break;
+            }
+            self.now = start;
+            self.occupied[level] &= !(1 << slot);
+            let from = self.slots[level * SLOTS + slot];
+            loop {
+                let index = self.multi.get_first(from);
+                let deadline = match self.multi.get(index) {
+                    Some(timer) => timer.deadline,
+                    None => break,
+                };
+                if deadline <= start {
+                    expired.push(self.multi.pop_front(from).unwrap().elem);
+                    continue;
+                }
+                // 降级到更低的层，索引来自该槽的队列，一定存在
+                let to = self.slot_for(deadline);
+                self.multi.move_to(index, self.slots[to]).unwrap();
+                self.occupy(to);
+            }
+        }
+        if now > self.now {
+            self.now = now;
+        }
+        expired
+    }
+
+    /// 取到定时器的个数
+    pub fn len(&self) -> usize {
+        self.multi.total_len()
+    }
+
+    /// 时间轮中是否没有定时器
+    pub fn is_empty(&self) -> bool {
+        self.multi.total_len() == 0
+    }
+
+    // 根据到期时间与当前时间计算定时器应该放入的槽
+    fn slot_for(&self, deadline: u64) -> usize {
+        if deadline <= self.now {
+            return READY;
+        }
+        let masked = (deadline ^ self.now) | (SLOTS as u64 - 1);
+        let level = (63 - masked.leading_zeros() as usize) / SLOT_BITS;
+        level * SLOTS + ((deadline >> (level * SLOT_BITS)) as usize & (SLOTS - 1))
+    }
+
+    fn occupy(&mut self, slot: usize) {
+        if slot != READY {
+            self.occupied[slot / SLOTS] |= 1 << (slot % SLOTS);
+        }
+    }
+
+    // 取到最早需要处理的非空槽，及该槽的起始时间
+    // 每层中非空的槽都在当前槽之后，且低层的槽总是比高层的槽先到达
+    fn next_slot(&self) -> Option<(usize, usize, u64)> {
+        for level in 0..LEVELS {
+            let shift = level * SLOT_BITS;
+            let current = (self.now >> shift) as usize & (SLOTS - 1);
+            let later = match current + 1 {
+                SLOTS => 0,
+                n => self.occupied[level] & (u64::MAX << n),
+            };
+            if later == 0 {
+                continue;
+            }
+            let slot = later.trailing_zeros() as usize;
+            let base = match shift + SLOT_BITS {
+                n if n >= 64 => 0,
+                n => self.now & !((1 << n) - 1),
+            };
+            return Some((level, slot, base + ((slot as u64) << shift)));
+        }
+        None
+    }
+}
+
+impl<T: Debug> Debug for TimerWheel<T> {
+    fn fmt(&self, f: &mut Formatter) -> FResult {
+        f.debug_struct("TimerWheel")
+            .field("multi", &self.multi)
+            .field("now", &self.now)
+            .finish()
+    }
+}
+
+#[test]
+fn test_timer_wheel(){
+    let mut wheel: TimerWheel<u64> = TimerWheel::new(100);
+    let a = wheel.schedule(105, 105);
+    let b = wheel.schedule(100 + 64 * 64 + 3, 100 + 64 * 64 + 3);
+    let c = wheel.schedule(90, 90);
+    assert_eq!(wheel.get(b), Some(&(100 + 64 * 64 + 3)));
+    assert_eq!(wheel.cancel(a), Ok(105));
+    assert_eq!(wheel.cancel(a), Err(DequeError::Stale(a.index())));
+    assert_eq!(wheel.advance(200), vec![90]);
+    assert_eq!(wheel.get(c), None);
+    assert_eq!(wheel.advance(100 + 64 * 64 + 2), vec![]);
+    assert_eq!(wheel.advance(100 + 64 * 64 + 3), vec![100 + 64 * 64 + 3]);
+    assert!(wheel.is_empty());
+
+    // 与逐一检查到期时间的模型对比，模型按添加的顺序保存定时器，并记录添加时是否已经过期
+    let mut model: Vec<(TimerHandle, u64, bool)> = Vec::new();
+    let mut seed: u64 = 7;
+    let mut now = wheel.now();
+    for _ in 0..3000 {
+        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        let r = seed >> 33;
+        match r % 4 {
+            0 | 1 => {
+                let deadline = now + (r >> 2) % (1 << (r % 20)) - 2;
+                model.push((wheel.schedule(deadline, deadline), deadline, deadline <= now));
+            },
+            2 if !model.is_empty() => {
+                let (handle, deadline, _) = model.remove((r >> 2) as usize % model.len());
+                assert_eq!(wheel.cancel(handle), Ok(deadline));
+            },
+            _ => {
+                now += (r >> 2) % (1 << (r % 16));
+                // 添加时已经过期的按添加顺序排在最前面，其余的按到期时间排序
+                let mut expect: Vec<u64> = model.iter().filter(|(_, _, overdue)| *overdue).map(|(_, d, _)| *d).collect();
+                let mut due: Vec<u64> = model.iter().filter(|(_, d, overdue)| !*overdue && *d <= now).map(|(_, d, _)| *d).collect();
+                due.sort();
+                expect.extend(due);
+                model.retain(|(_, d, _)| *d > now);
+                assert_eq!(wheel.advance(now), expect);
+            },
+        }
+    }
+    assert_eq!(wheel.len(), model.len());
+}
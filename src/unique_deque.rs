@@ -0,0 +1,172 @@
+//! 去重的双端队列
+//! 在linked_map的基础上增加去重策略，每个元素带有一个键，同一个键在队列中最多出现一次
+//! 压入一个键已在队列中的元素时，根据去重策略忽略、原地替换或移动到尾部，并返回已有元素的索引
+//! 例如：每帧收集脏实体的通知，同一个实体在一帧中只需要处理一次
+
+use std::borrow::Borrow;
+use std::fmt::{Debug, Formatter, Result as FResult};
+use std::hash::Hash;
+
+use crate::linked_map::{ LinkedMap, Iter };
+
+/// 去重策略，决定压入一个键已在队列中的元素时如何处理
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DedupPolicy {
+    /// 忽略新元素，保留已有元素及其位置
+    #[default]
+    Ignore,
+    /// 用新元素原地替换已有元素，位置不变
+    Replace,
+    /// 用新元素替换已有元素，并将其移动到队列尾部
+    MoveToBack,
+}
+
+/// 去重的双端队列
+pub struct UniqueDeque<K, T>{
+    map: LinkedMap<K, T>,
+    policy: DedupPolicy,
+}
+
+impl<K: Hash + Eq + Clone, T> Default for UniqueDeque<K, T> {
+    fn default() -> UniqueDeque<K, T> {
+        UniqueDeque::new(DedupPolicy::default())
+    }
+}
+
+impl<K: Hash + Eq + Clone, T> UniqueDeque<K, T> {
+    /// 创建一个使用policy去重的队列
+    pub fn new(policy: DedupPolicy) -> UniqueDeque<K, T> {
+        Self {
+            map: LinkedMap::new(),
+            policy,
+        }
+    }
+
+    /// 队列的去重策略
+    pub fn policy(&self) -> DedupPolicy {
+        self.policy
+    }
+
+    /// Append an element with the key to the UniqueDeque, if the key is already queued, the element is handled by the policy. return a index
+    /// 在尾部压入一个带键的元素，返回元素索引；如果键已在队列中，则根据去重策略处理，并返回已有元素的索引
+    pub fn push_back(&mut self, key: K, elem: T) -> usize {
+        let index = match self.map.index_of(&key) {
+            Some(index) => index,
+            None => return self.map.insert_full(key, elem).0,
+        };
+        match self.policy {
+            DedupPolicy::Ignore => (),
+            DedupPolicy::Replace => {
+                self.map.insert(key, elem);
+            },
+            DedupPolicy::MoveToBack => {
+                self.map.move_to_back(&key);
+                self.map.insert(key, elem);
+            },
+        }
+        index
+    }
+
+    /// Removes the first element from the UniqueDeque and returns it with its key, or None if it is empty.
+    /// 从队列头部弹出一个元素及其键，如果队列中没有元素，则返回None
+    pub fn pop_front(&mut self) -> Option<(K, T)> {
+        self.map.pop_front()
+    }
+
+    /// Removes the last element from the UniqueDeque and returns it with its key, or None if it is empty.
+    /// 从队列尾部弹出一个元素及其键，如果队列中没有元素，则返回None
+    pub fn pop_back(&mut self) -> Option<(K, T)> {
+        self.map.pop_back()
+    }
+
+    /// Removes and returns the element of the key.
+    /// 删除键对应的元素，并返回该元素
+    pub fn remove<Q: Hash + Eq + ?Sized>(&mut self, key: &Q) -> Option<T> where K: Borrow<Q> {
+        self.map.remove(key)
+    }
+
+    /// Returns the index of the element of the key.
+    /// 取到键对应元素的索引
+    pub fn index_of<Q: Hash + Eq + ?Sized>(&self, key: &Q) -> Option<usize> where K: Borrow<Q> {
+        self.map.index_of(key)
+    }
+
+    /// 队列中是否存在键
+    pub fn contains<Q: Hash + Eq + ?Sized>(&self, key: &Q) -> bool where K: Borrow<Q> {
+        self.map.contains_key(key)
+    }
+
+    /// Returns a reference to the element of the key.
+    /// 取到键对应元素的引用
+    pub fn get<Q: Hash + Eq + ?Sized>(&self, key: &Q) -> Option<&T> where K: Borrow<Q> {
+        self.map.get(key)
+    }
+
+    /// Returns a mutable reference to the element of the key.
+    /// 取到键对应元素的可变引用
+    pub fn get_mut<Q: Hash + Eq + ?Sized>(&mut self, key: &Q) -> Option<&mut T> where K: Borrow<Q> {
+        self.map.get_mut(key)
+    }
+
+    /// 取到队列元素个数
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// 队列是否为空
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// clear UniqueDeque
+    /// 清空队列
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    /// 创建队列的迭代器
+    pub fn iter(&self) -> Iter<'_, K, T> {
+        self.map.iter()
+    }
+}
+
+impl<K: Debug, T: Debug> Debug for UniqueDeque<K, T> {
+    fn fmt(&self, f: &mut Formatter) -> FResult {
+        f.debug_struct("UniqueDeque")
+            .field("map", &self.map)
+            .field("policy", &self.policy)
+            .finish()
+    }
+}
+
+#[test]
+fn test_unique(){
+    fn order(deque: &UniqueDeque<u32, char>) -> Vec<(u32, char)> {
+        deque.iter().map(|(k, elem)| (*k, *elem)).collect()
+    }
+
+    let mut ignore: UniqueDeque<u32, char> = UniqueDeque::new(DedupPolicy::Ignore);
+    let mut replace: UniqueDeque<u32, char> = UniqueDeque::new(DedupPolicy::Replace);
+    let mut move_to_back: UniqueDeque<u32, char> = UniqueDeque::new(DedupPolicy::MoveToBack);
+    for deque in [&mut ignore, &mut replace, &mut move_to_back] {
+        let a = deque.push_back(1, 'a');
+        deque.push_back(2, 'b');
+        assert_eq!(deque.push_back(1, 'c'), a);
+        assert_eq!(deque.len(), 2);
+        assert_eq!(deque.index_of(&1), Some(a));
+    }
+    assert_eq!(order(&ignore), vec![(1, 'a'), (2, 'b')]);
+    assert_eq!(order(&replace), vec![(1, 'c'), (2, 'b')]);
+    assert_eq!(order(&move_to_back), vec![(2, 'b'), (1, 'c')]);
+
+    // 弹出后，同一个键可以再次入队
+    assert_eq!(ignore.pop_front(), Some((1, 'a')));
+    assert!(!ignore.contains(&1));
+    ignore.push_back(1, 'd');
+    assert_eq!(order(&ignore), vec![(2, 'b'), (1, 'd')]);
+    assert_eq!(ignore.remove(&2), Some('b'));
+    *ignore.get_mut(&1).unwrap() = 'e';
+    assert_eq!(ignore.get(&1), Some(&'e'));
+    assert_eq!(ignore.pop_back(), Some((1, 'e')));
+    assert!(ignore.is_empty());
+}